@@ -0,0 +1,176 @@
+use crate::time::{EventTime, parse_duration};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use ical::parser::ical::component::IcalAlarm;
+
+/// Upper bound on REPEAT, so a malformed feed cannot flood a room.
+const MAX_REPEAT: u32 = 100;
+
+/// When an alarm goes off (the TRIGGER property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmTrigger {
+    /// Offset from the start of the event, or from its end with RELATED=END
+    Relative { offset: Duration, from_end: bool },
+    /// A fixed instant (VALUE=DATE-TIME)
+    Absolute(DateTime<Utc>),
+}
+
+/// A VALARM component attached to an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventAlarm {
+    /// ACTION, e.g. `DISPLAY` or `EMAIL`
+    pub action: Option<String>,
+    pub description: Option<String>,
+    pub trigger: AlarmTrigger,
+    /// Number of additional repetitions after the first trigger (REPEAT)
+    pub repeat: u32,
+    /// Delay between repetitions (DURATION)
+    pub repeat_interval: Option<Duration>,
+}
+
+impl EventAlarm {
+    /// Build an alarm from a parsed VALARM component.
+    pub fn from_ical(component: &IcalAlarm) -> Result<Self> {
+        let property = |name: &str| component.properties.iter().find(|p| p.name == name);
+        let value = |name: &str| property(name).and_then(|p| p.value.as_deref());
+
+        let trigger_property = property("TRIGGER").ok_or_else(|| anyhow!("Missing TRIGGER"))?;
+        let trigger_value = trigger_property
+            .value
+            .as_deref()
+            .ok_or_else(|| anyhow!("Empty TRIGGER"))?;
+        let param = |name: &str| {
+            trigger_property
+                .params
+                .as_ref()?
+                .iter()
+                .find(|(key, _)| key == name)
+                .and_then(|(_, values)| values.first())
+                .map(|v| v.to_uppercase())
+        };
+
+        let trigger = if param("VALUE").as_deref() == Some("DATE-TIME") {
+            let time: EventTime = trigger_value.parse()?;
+            AlarmTrigger::Absolute(time.to_utc())
+        } else {
+            AlarmTrigger::Relative {
+                offset: parse_duration(trigger_value)?,
+                from_end: param("RELATED").as_deref() == Some("END"),
+            }
+        };
+
+        Ok(EventAlarm {
+            action: value("ACTION").map(|s| s.to_string()),
+            description: value("DESCRIPTION").map(|s| s.to_string()),
+            trigger,
+            repeat: value("REPEAT")
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(0)
+                .min(MAX_REPEAT),
+            repeat_interval: value("DURATION").and_then(|v| parse_duration(v).ok()),
+        })
+    }
+
+    /// Times at which the alarm goes off for an event with the given start
+    /// and end, including repetitions. End-related alarms fall back to the
+    /// start when the event has no end.
+    pub fn trigger_times(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Vec<DateTime<Utc>> {
+        let first = match self.trigger {
+            AlarmTrigger::Absolute(time) => Some(time),
+            AlarmTrigger::Relative {
+                offset,
+                from_end: false,
+            } => start.map(|start| start + offset),
+            AlarmTrigger::Relative {
+                offset,
+                from_end: true,
+            } => end.or(start).map(|end| end + offset),
+        };
+        let Some(first) = first else {
+            return Vec::new();
+        };
+
+        let mut times = vec![first];
+        if let Some(interval) = self.repeat_interval.filter(|i| *i > Duration::zero()) {
+            times.extend((1..=self.repeat as i32).map(|n| first + interval * n));
+        }
+        times
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ical::property::Property;
+
+    /// Property name, optional parameter and value.
+    type PropertySpec<'a> = (&'a str, Option<(&'a str, &'a str)>, &'a str);
+
+    fn alarm(properties: &[PropertySpec]) -> IcalAlarm {
+        IcalAlarm {
+            properties: properties
+                .iter()
+                .map(|(name, param, value)| Property {
+                    name: name.to_string(),
+                    params: param.map(|(k, v)| vec![(k.to_string(), vec![v.to_string()])]),
+                    value: Some(value.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse::<EventTime>().unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_relative_trigger_with_repeat() {
+        let alarm = EventAlarm::from_ical(&alarm(&[
+            ("ACTION", None, "DISPLAY"),
+            ("TRIGGER", None, "-PT15M"),
+            ("REPEAT", None, "2"),
+            ("DURATION", None, "PT5M"),
+        ]))
+        .unwrap();
+
+        let start = utc("20251201T100000Z");
+        let times: Vec<String> = alarm
+            .trigger_times(Some(start), None)
+            .iter()
+            .map(|t| t.format("%H:%M").to_string())
+            .collect();
+        assert_eq!(times, vec!["09:45", "09:50", "09:55"]);
+    }
+
+    #[test]
+    fn test_end_related_and_absolute_triggers() {
+        let end_related =
+            EventAlarm::from_ical(&alarm(&[("TRIGGER", Some(("RELATED", "END")), "-PT5M")]))
+                .unwrap();
+        assert_eq!(
+            end_related.trigger_times(Some(utc("20251201T100000Z")), Some(utc("20251201T110000Z"))),
+            vec![utc("20251201T105500Z")]
+        );
+
+        let absolute = EventAlarm::from_ical(&alarm(&[(
+            "TRIGGER",
+            Some(("VALUE", "DATE-TIME")),
+            "20251130T090000Z",
+        )]))
+        .unwrap();
+        assert_eq!(
+            absolute.trigger,
+            AlarmTrigger::Absolute(utc("20251130T090000Z"))
+        );
+        assert_eq!(
+            absolute.trigger_times(None, None),
+            vec![utc("20251130T090000Z")]
+        );
+
+        assert!(EventAlarm::from_ical(&alarm(&[("ACTION", None, "DISPLAY")])).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+use crate::ical::IcalCalendar;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::{Mutex, RwLock};
+
+/// A parsed calendar and when it was fetched.
+#[derive(Clone)]
+pub struct CalendarSnapshot {
+    pub calendar: Arc<IcalCalendar>,
+    pub fetched_at: DateTime<Utc>,
+    /// ETag validator from the response, sent back as If-None-Match
+    pub etag: Option<String>,
+    /// Last-Modified validator from the response, sent back as If-Modified-Since
+    pub last_modified: Option<String>,
+    /// The latest refresh failed (or none succeeded since startup), so this
+    /// copy may be out of date
+    pub outdated: bool,
+}
+
+/// Shared in-memory cache of a parsed calendar feed.
+///
+/// The feed is refreshed in the background every refresh interval. Readers
+/// always get the cached calendar if there is one; when it is stale a refresh
+/// is started and the stale copy is served meanwhile, so a slow upstream
+/// never delays a reply. Only the very first read waits on the network.
+///
+/// Refreshes are conditional requests: when the server answers 304 Not
+/// Modified the parsed calendar is kept as is.
+///
+/// With [`CalendarCache::persist_to`] the last good feed is also kept on
+/// disk, so the bot can answer from it after a restart during an outage.
+pub struct CalendarCache {
+    url: String,
+    client: reqwest::Client,
+    refresh_interval: Duration,
+    persist_path: Option<PathBuf>,
+    snapshot: RwLock<Option<CalendarSnapshot>>,
+    /// Held while fetching so concurrent refreshes share one request
+    fetch_lock: Mutex<()>,
+}
+
+impl CalendarCache {
+    pub fn new(url: &str, refresh_interval: Duration) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+            refresh_interval,
+            persist_path: None,
+            snapshot: RwLock::new(None),
+            fetch_lock: Mutex::new(()),
+        }
+    }
+
+    /// Keep the last good feed in the given file, and start from the copy
+    /// already there, if any. It is marked outdated until a refresh succeeds.
+    pub fn persist_to(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match load_persisted(&path) {
+            Ok(Some(snapshot)) => *self.snapshot.get_mut() = Some(snapshot),
+            Ok(None) => {}
+            Err(e) => eprintln!("Ignoring cached calendar {}: {}", path.display(), e),
+        }
+        self.persist_path = Some(path);
+        self
+    }
+
+    /// The cached calendar, fetching it first if nothing is cached yet.
+    pub async fn get(self: &Arc<Self>) -> Result<CalendarSnapshot> {
+        let cached = self.snapshot.read().await.clone();
+        match cached {
+            Some(snapshot) => {
+                if self.is_stale(&snapshot, Utc::now()) && self.fetch_lock.try_lock().is_ok() {
+                    let cache = Arc::clone(self);
+                    tokio::spawn(async move {
+                        if let Err(e) = cache.refresh().await {
+                            eprintln!("Failed to refresh calendar {}: {}", cache.url, e);
+                        }
+                    });
+                }
+                Ok(snapshot)
+            }
+            None => self.refresh().await,
+        }
+    }
+
+    /// Fetch the feed now and update the cache. Callers that arrive while a
+    /// fetch is in flight get its result instead of starting another.
+    pub async fn refresh(&self) -> Result<CalendarSnapshot> {
+        let requested_at = Utc::now();
+        let _fetching = self.fetch_lock.lock().await;
+
+        let previous = self.snapshot.read().await.clone();
+        if let Some(snapshot) = &previous
+            && snapshot.fetched_at >= requested_at
+        {
+            return Ok(snapshot.clone());
+        }
+
+        match self.fetch(previous.as_ref()).await {
+            Ok(snapshot) => {
+                *self.snapshot.write().await = Some(snapshot.clone());
+                Ok(snapshot)
+            }
+            Err(e) => {
+                if let Some(snapshot) = self.snapshot.write().await.as_mut() {
+                    snapshot.outdated = true;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetch the feed, revalidating the previous snapshot if there is one.
+    async fn fetch(&self, previous: Option<&CalendarSnapshot>) -> Result<CalendarSnapshot> {
+        let mut request = self.client.get(&self.url);
+        if let Some(previous) = previous {
+            if let Some(etag) = &previous.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &previous.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(previous) = previous
+        {
+            // The copy on disk is dated by its mtime, so mark it revalidated too
+            if let Some(path) = &self.persist_path
+                && let Err(e) = touch(path)
+            {
+                eprintln!("Failed to update cached calendar {}: {}", path.display(), e);
+            }
+            return Ok(CalendarSnapshot {
+                fetched_at: Utc::now(),
+                outdated: false,
+                ..previous.clone()
+            });
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error: {}", response.status()));
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let content = response.text().await?;
+        let calendar = IcalCalendar::parse_ical_content(&content)?;
+
+        // Only feeds that parse are kept as the fallback copy
+        if let Some(path) = &self.persist_path
+            && let Err(e) = persist(path, &content)
+        {
+            eprintln!("Failed to write cached calendar {}: {}", path.display(), e);
+        }
+
+        Ok(CalendarSnapshot {
+            calendar: Arc::new(calendar),
+            fetched_at: Utc::now(),
+            etag,
+            last_modified,
+            outdated: false,
+        })
+    }
+
+    /// Refresh the feed every refresh interval until the task is aborted.
+    pub fn spawn_refresher(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            let period = cache
+                .refresh_interval
+                .to_std()
+                .unwrap_or(std::time::Duration::from_secs(300));
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = cache.refresh().await {
+                    eprintln!("Failed to refresh calendar {}: {}", cache.url, e);
+                }
+            }
+        })
+    }
+
+    fn is_stale(&self, snapshot: &CalendarSnapshot, now: DateTime<Utc>) -> bool {
+        now - snapshot.fetched_at >= self.refresh_interval
+    }
+}
+
+/// Load a feed written by [`persist`], dated by the file's modification time.
+fn load_persisted(path: &PathBuf) -> Result<Option<CalendarSnapshot>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    let modified = fs::metadata(path)?.modified()?;
+
+    Ok(Some(CalendarSnapshot {
+        calendar: Arc::new(IcalCalendar::parse_ical_content(&content)?),
+        fetched_at: modified.into(),
+        etag: None,
+        last_modified: None,
+        outdated: true,
+    }))
+}
+
+/// Write the feed through a temporary file so a crash never leaves a
+/// truncated copy behind.
+fn persist(path: &PathBuf, content: &str) -> Result<()> {
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+/// Set the modification time of a persisted feed to now, if there is one.
+fn touch(path: &PathBuf) -> Result<()> {
+    if path.exists() {
+        fs::File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(SystemTime::now())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const FEED: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//Test//EN\r\n\
+        BEGIN:VEVENT\r\nUID:a@example.com\r\nDTSTART:20251201T100000Z\r\nSUMMARY:Standup\r\n\
+        END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    /// Serve the test feed over HTTP with an ETag, counting requests.
+    /// Requests that present the current ETag get 304 Not Modified.
+    fn serve_feed() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/calendar.ics", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buffer = [0; 4096];
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        FEED.len(),
+                        FEED
+                    )
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_fresh_calendar_is_served_from_cache() {
+        let (url, requests) = serve_feed();
+        let cache = Arc::new(CalendarCache::new(&url, Duration::minutes(5)));
+
+        let first = cache.get().await.unwrap();
+        let second = cache.get().await.unwrap();
+
+        assert_eq!(first.calendar.events.len(), 1);
+        assert!(Arc::ptr_eq(&first.calendar, &second.calendar));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_calendar_is_served_while_refreshing() {
+        let (url, requests) = serve_feed();
+        let cache = Arc::new(CalendarCache::new(&url, Duration::zero()));
+
+        let first = cache.get().await.unwrap();
+        let stale = cache.get().await.unwrap();
+        assert!(Arc::ptr_eq(&first.calendar, &stale.calendar));
+
+        // The background refresh replaces the snapshot
+        let refreshed = cache.refresh().await.unwrap();
+        assert!(refreshed.fetched_at > first.fetched_at);
+        assert!(requests.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_not_modified_reuses_parsed_calendar() {
+        let (url, requests) = serve_feed();
+        let path =
+            std::env::temp_dir().join(format!("calendar-revalidated-{}.ics", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let cache = Arc::new(CalendarCache::new(&url, Duration::minutes(5)).persist_to(&path));
+
+        let first = cache.refresh().await.unwrap();
+        assert_eq!(first.etag.as_deref(), Some("\"v1\""));
+
+        // Date the copy on disk back as if the feed had not changed for days
+        let days_ago = SystemTime::now() - std::time::Duration::from_secs(3 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(days_ago)
+            .unwrap();
+
+        let revalidated = cache.refresh().await.unwrap();
+        assert!(Arc::ptr_eq(&first.calendar, &revalidated.calendar));
+        assert!(revalidated.fetched_at > first.fetched_at);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // After a restart the copy counts from the revalidation, not the last change
+        let restarted = load_persisted(&path).unwrap().unwrap();
+        assert!(restarted.fetched_at >= first.fetched_at);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persisted_feed_is_used_after_restart() {
+        let (url, _) = serve_feed();
+        let path = std::env::temp_dir().join(format!("calendar-cache-{}.ics", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let cache = Arc::new(CalendarCache::new(&url, Duration::minutes(5)).persist_to(&path));
+        assert!(!cache.refresh().await.unwrap().outdated);
+        assert!(path.exists());
+
+        // After a restart with the upstream down, the copy on disk is served
+        let unreachable = "http://127.0.0.1:9/calendar.ics";
+        let restarted =
+            Arc::new(CalendarCache::new(unreachable, Duration::minutes(5)).persist_to(&path));
+        let snapshot = restarted.get().await.unwrap();
+        assert!(snapshot.outdated);
+        assert_eq!(snapshot.calendar.events.len(), 1);
+        assert!(restarted.refresh().await.is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
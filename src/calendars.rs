@@ -0,0 +1,231 @@
+use crate::cache::{CalendarCache, CalendarSnapshot};
+use crate::ical::{EventSource, IcalCalendar};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The configured calendar feeds, each with its own cache, read together as
+/// one merged calendar.
+///
+/// With a single feed its snapshot is returned as is. With several, every
+/// event and task in the merged calendar carries the name and color of the
+/// feed it came from. A merged calendar is reused until one of its feeds
+/// changes, so callers can tell unchanged snapshots apart with `Arc::ptr_eq`.
+#[derive(Default)]
+pub struct CalendarSet {
+    feeds: Vec<(EventSource, Arc<CalendarCache>)>,
+    /// Latest merge of each combination of feeds, by feed names
+    merged: Mutex<HashMap<Vec<String>, Merged>>,
+}
+
+/// A merged calendar and the feed calendars it was built from.
+struct Merged {
+    feeds: Vec<Arc<IcalCalendar>>,
+    calendar: Arc<IcalCalendar>,
+}
+
+impl CalendarSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named feed.
+    pub fn with_feed(mut self, source: EventSource, cache: CalendarCache) -> Self {
+        self.feeds.push((source, Arc::new(cache)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.feeds.is_empty()
+    }
+
+    /// Names of the feeds, in configuration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.feeds
+            .iter()
+            .map(|(source, _)| source.name.as_str())
+            .collect()
+    }
+
+    /// Check that every name refers to a feed in the set.
+    pub fn check_names(&self, names: &[String]) -> Result<()> {
+        match names
+            .iter()
+            .find(|name| !self.feeds.iter().any(|(source, _)| &source.name == *name))
+        {
+            Some(unknown) => Err(anyhow!(
+                "Unknown calendar: {}. Available: {}",
+                unknown,
+                self.names().join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Refresh every feed in the background.
+    pub fn spawn_refreshers(&self) {
+        for (_, cache) in &self.feeds {
+            cache.spawn_refresher();
+        }
+    }
+
+    /// The named feeds merged into one calendar, or all feeds if no names are
+    /// given. Feeds that cannot be fetched are left out and the result is
+    /// marked outdated; it is an error only if none can be fetched.
+    pub async fn get(&self, names: &[String]) -> Result<CalendarSnapshot> {
+        self.check_names(names)?;
+
+        if let [(_, cache)] = self.feeds.as_slice() {
+            return cache.get().await;
+        }
+
+        let mut snapshots = Vec::new();
+        let mut failed = None;
+        for (source, cache) in &self.feeds {
+            if !names.is_empty() && !names.contains(&source.name) {
+                continue;
+            }
+            match cache.get().await {
+                Ok(snapshot) => snapshots.push((source, snapshot)),
+                Err(e) => {
+                    eprintln!("Failed to fetch calendar '{}': {}", source.name, e);
+                    failed = Some(e);
+                }
+            }
+        }
+
+        let (Some(fetched_at), Some(outdated)) = (
+            snapshots.iter().map(|(_, s)| s.fetched_at).min(),
+            snapshots
+                .iter()
+                .map(|(_, s)| s.outdated)
+                .reduce(|a, b| a || b),
+        ) else {
+            return Err(failed.unwrap_or_else(|| anyhow!("No calendars configured")));
+        };
+
+        Ok(CalendarSnapshot {
+            calendar: self.merge(&snapshots),
+            fetched_at,
+            etag: None,
+            last_modified: None,
+            outdated: outdated || failed.is_some(),
+        })
+    }
+
+    /// Merge the feed snapshots, reusing the previous merge of the same feeds
+    /// if none of their calendars changed since.
+    fn merge(&self, snapshots: &[(&EventSource, CalendarSnapshot)]) -> Arc<IcalCalendar> {
+        let names: Vec<String> = snapshots
+            .iter()
+            .map(|(source, _)| source.name.clone())
+            .collect();
+        let feeds: Vec<Arc<IcalCalendar>> = snapshots
+            .iter()
+            .map(|(_, snapshot)| Arc::clone(&snapshot.calendar))
+            .collect();
+
+        let mut merged = self.merged.lock().expect("merged calendars lock");
+        if let Some(previous) = merged.get(&names)
+            && previous.feeds.len() == feeds.len()
+            && previous
+                .feeds
+                .iter()
+                .zip(&feeds)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+        {
+            return Arc::clone(&previous.calendar);
+        }
+
+        let calendar = Arc::new(IcalCalendar::merge(
+            snapshots
+                .iter()
+                .map(|(source, snapshot)| (*source, snapshot.calendar.as_ref())),
+        ));
+        merged.insert(
+            names,
+            Merged {
+                feeds,
+                calendar: Arc::clone(&calendar),
+            },
+        );
+        calendar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::fs;
+
+    /// A feed served from a persisted copy, so no request is made while it is fresh.
+    fn feed(name: &str, summary: &str) -> (EventSource, CalendarCache) {
+        let path =
+            std::env::temp_dir().join(format!("calendar-set-{}-{}.ics", name, std::process::id()));
+        fs::write(
+            &path,
+            format!(
+                "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:{}@example.com\r\n\
+                 DTSTART:20251201T100000Z\r\nSUMMARY:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+                name, summary
+            ),
+        )
+        .unwrap();
+        let cache = CalendarCache::new("http://127.0.0.1:9/calendar.ics", Duration::minutes(5))
+            .persist_to(&path);
+        fs::remove_file(&path).unwrap();
+
+        let source = EventSource {
+            name: name.to_string(),
+            color: Some("blue".to_string()),
+        };
+        (source, cache)
+    }
+
+    #[tokio::test]
+    async fn test_merges_selected_calendars() {
+        let (team, team_cache) = feed("team", "Standup");
+        let (holidays, holidays_cache) = feed("holidays", "New Year");
+        let calendars = CalendarSet::new()
+            .with_feed(team, team_cache)
+            .with_feed(holidays, holidays_cache);
+
+        let all = calendars.get(&[]).await.unwrap();
+        let names: Vec<_> = all
+            .calendar
+            .events
+            .iter()
+            .map(|e| e.source.as_ref().unwrap().name.as_str())
+            .collect();
+        assert_eq!(names, vec!["team", "holidays"]);
+
+        let team_only = calendars.get(&["team".to_string()]).await.unwrap();
+        assert_eq!(team_only.calendar.events.len(), 1);
+        assert_eq!(
+            team_only.calendar.events[0].summary.as_deref(),
+            Some("Standup")
+        );
+
+        assert!(calendars.get(&["other".to_string()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_feeds_reuse_the_merged_calendar() {
+        let (team, team_cache) = feed("team-reuse", "Standup");
+        let (holidays, holidays_cache) = feed("holidays-reuse", "New Year");
+        let calendars = CalendarSet::new()
+            .with_feed(team, team_cache)
+            .with_feed(holidays, holidays_cache);
+
+        let first = calendars.get(&[]).await.unwrap();
+        let second = calendars.get(&[]).await.unwrap();
+        assert!(Arc::ptr_eq(&first.calendar, &second.calendar));
+
+        // Another selection is merged separately
+        let team_only = calendars.get(&["team-reuse".to_string()]).await.unwrap();
+        assert!(!Arc::ptr_eq(&first.calendar, &team_only.calendar));
+        let again = calendars.get(&[]).await.unwrap();
+        assert!(Arc::ptr_eq(&first.calendar, &again.calendar));
+    }
+}
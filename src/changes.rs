@@ -0,0 +1,467 @@
+use crate::ical::{CalendarEvent, IcalCalendar};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+/// How far outside the window an occurrence is still matched, so events
+/// moved into or out of the window count as rescheduled.
+const MATCH_MARGIN_DAYS: i64 = 366;
+
+/// A difference between two snapshots of a calendar.
+#[derive(Debug, Clone)]
+pub enum CalendarChange {
+    Added(CalendarEvent),
+    Rescheduled {
+        before: CalendarEvent,
+        after: CalendarEvent,
+    },
+    LocationChanged {
+        before: CalendarEvent,
+        after: CalendarEvent,
+    },
+    /// Removed from the feed or marked STATUS:CANCELLED, as it was before
+    Cancelled(CalendarEvent),
+}
+
+impl CalendarChange {
+    /// The event as it is now, or as it was if it has been cancelled.
+    pub fn event(&self) -> &CalendarEvent {
+        match self {
+            CalendarChange::Added(event) | CalendarChange::Cancelled(event) => event,
+            CalendarChange::Rescheduled { after, .. }
+            | CalendarChange::LocationChanged { after, .. } => after,
+        }
+    }
+
+    /// What the changes to occurrences of one series must share to be
+    /// announced together.
+    fn kind(&self) -> ChangeKind {
+        match self {
+            CalendarChange::Added(_) => ChangeKind::Added,
+            CalendarChange::Rescheduled { before, after } => {
+                ChangeKind::Rescheduled(start_of(after).zip(start_of(before)).map(|(a, b)| a - b))
+            }
+            CalendarChange::LocationChanged { before, after } => {
+                ChangeKind::LocationChanged(before.location.clone(), after.location.clone())
+            }
+            CalendarChange::Cancelled(_) => ChangeKind::Cancelled,
+        }
+    }
+}
+
+/// The same change to one or more occurrences of an event, reported by
+/// its earliest occurrence.
+#[derive(Debug, Clone)]
+pub struct SeriesChange {
+    pub change: CalendarChange,
+    /// Number of occurrences in the window changed this way
+    pub occurrences: usize,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum ChangeKind {
+    Added,
+    /// By how much the occurrences moved
+    Rescheduled(Option<Duration>),
+    LocationChanged(Option<String>, Option<String>),
+    Cancelled,
+}
+
+/// Identifies an occurrence across snapshots: its calendar, UID and
+/// original start.
+type OccurrenceKey = (Option<String>, String, Option<DateTime<Utc>>);
+
+/// Compare two snapshots of a calendar and report the changes to occurrences
+/// that start within the window in either snapshot. Occurrences of a series
+/// that changed the same way, such as all moving by an hour, are reported
+/// as one change.
+pub fn diff_calendars(
+    previous: &IcalCalendar,
+    current: &IcalCalendar,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Vec<SeriesChange> {
+    let mut changes: Vec<SeriesChange> = Vec::new();
+    let mut groups: HashMap<_, usize> = HashMap::new();
+    for change in diff_occurrences(previous, current, window_start, window_end) {
+        let event = change.event();
+        let group = (
+            event.source.as_ref().map(|source| source.name.clone()),
+            event.uid.clone(),
+            change.kind(),
+        );
+        match groups.get(&group) {
+            Some(&index) => changes[index].occurrences += 1,
+            None => {
+                groups.insert(group, changes.len());
+                changes.push(SeriesChange {
+                    change,
+                    occurrences: 1,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// Compare two snapshots occurrence by occurrence. Occurrences are matched
+/// by UID and RECURRENCE-ID, or by their position in the series when the
+/// whole series moved; events without a UID cannot be tracked and are
+/// ignored.
+fn diff_occurrences(
+    previous: &IcalCalendar,
+    current: &IcalCalendar,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Vec<CalendarChange> {
+    let margin = Duration::days(MATCH_MARGIN_DAYS);
+    let before = occurrences_by_key(previous, window_start - margin, window_end + margin);
+    let after = occurrences_by_key(current, window_start - margin, window_end + margin);
+    let moved = moved_occurrences(previous, current, &before, &after, window_end + margin);
+
+    let in_window = |event: &CalendarEvent| {
+        event.start_time.is_some_and(|start| {
+            let start = start.to_utc();
+            start >= window_start && start <= window_end
+        })
+    };
+    let keys: BTreeSet<&OccurrenceKey> = before
+        .iter()
+        .chain(after.iter())
+        .filter(|(_, event)| in_window(event))
+        .map(|(key, _)| key)
+        .collect();
+
+    let mut changes = Vec::new();
+    let moved_from: HashMap<&OccurrenceKey, &OccurrenceKey> =
+        moved.iter().map(|(old, new)| (new, old)).collect();
+    let mut paired = BTreeSet::new();
+    for key in keys {
+        // An occurrence that moved with its series is compared under its old key
+        let old_key = moved_from.get(key).copied().unwrap_or(key);
+        let new_key = moved.get(old_key).unwrap_or(key);
+        if !paired.insert(old_key) {
+            continue;
+        }
+
+        let change = match (before.get(old_key), after.get(new_key)) {
+            (Some(old), None) if in_window(old) && !old.is_cancelled() => {
+                CalendarChange::Cancelled(old.clone())
+            }
+            (None, Some(new)) if in_window(new) && !new.is_cancelled() => {
+                CalendarChange::Added(new.clone())
+            }
+            (Some(old), Some(new)) if new.is_cancelled() && !old.is_cancelled() => {
+                CalendarChange::Cancelled(old.clone())
+            }
+            (Some(old), Some(new)) if new.is_cancelled() || old.is_cancelled() => continue,
+            (Some(old), Some(new)) if start_of(old) != start_of(new) => {
+                CalendarChange::Rescheduled {
+                    before: old.clone(),
+                    after: new.clone(),
+                }
+            }
+            (Some(old), Some(new)) if old.location != new.location => {
+                CalendarChange::LocationChanged {
+                    before: old.clone(),
+                    after: new.clone(),
+                }
+            }
+            _ => continue,
+        };
+        changes.push(change);
+    }
+
+    changes.sort_by_key(|change| start_of(change.event()));
+    changes
+}
+
+fn occurrences_by_key(
+    calendar: &IcalCalendar,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> HashMap<OccurrenceKey, CalendarEvent> {
+    calendar
+        .occurrences_between(window_start, window_end)
+        .into_iter()
+        .filter_map(|event| {
+            let key = (
+                event.source.as_ref().map(|source| source.name.clone()),
+                event.uid.clone()?,
+                event.recurrence_id.map(|id| id.to_utc()),
+            );
+            Some((key, event))
+        })
+        .collect()
+}
+
+/// Occurrences that are in only one snapshot but hold the same position in
+/// their series, as when the DTSTART of a recurring event changes and every
+/// occurrence gets a new RECURRENCE-ID. Maps the old key to the new one.
+fn moved_occurrences(
+    previous: &IcalCalendar,
+    current: &IcalCalendar,
+    before: &HashMap<OccurrenceKey, CalendarEvent>,
+    after: &HashMap<OccurrenceKey, CalendarEvent>,
+    until: DateTime<Utc>,
+) -> HashMap<OccurrenceKey, OccurrenceKey> {
+    let unmatched = |from: &HashMap<OccurrenceKey, CalendarEvent>,
+                     other: &HashMap<OccurrenceKey, CalendarEvent>| {
+        from.keys()
+            .filter(|key| key.2.is_some() && !other.contains_key(*key))
+            .cloned()
+            .collect::<Vec<OccurrenceKey>>()
+    };
+    let removed = unmatched(before, after);
+    let added = unmatched(after, before);
+
+    let series: BTreeSet<(Option<String>, String)> = removed
+        .iter()
+        .filter(|old| added.iter().any(|new| new.0 == old.0 && new.1 == old.1))
+        .map(|(source, uid, _)| (source.clone(), uid.clone()))
+        .collect();
+
+    let mut moved = HashMap::new();
+    for (source, uid) in series {
+        let old_positions = series_positions(previous, &source, &uid, until);
+        let new_positions = series_positions(current, &source, &uid, until);
+        let added_by_position: HashMap<usize, &OccurrenceKey> = added
+            .iter()
+            .filter(|key| key.0 == source && key.1 == uid)
+            .filter_map(|key| Some((*new_positions.get(&key.2?)?, key)))
+            .collect();
+
+        for key in removed.iter().filter(|key| key.0 == source && key.1 == uid) {
+            if let Some(position) = key.2.and_then(|id| old_positions.get(&id))
+                && let Some(new_key) = added_by_position.get(position)
+            {
+                moved.insert(key.clone(), (*new_key).clone());
+            }
+        }
+    }
+    moved
+}
+
+/// Position of each occurrence of a recurring event in its series, counted
+/// from DTSTART, by original start.
+fn series_positions(
+    calendar: &IcalCalendar,
+    source: &Option<String>,
+    uid: &str,
+    until: DateTime<Utc>,
+) -> HashMap<DateTime<Utc>, usize> {
+    let Some(master) = calendar.events.iter().find(|event| {
+        event.recurrence_id.is_none()
+            && event.uid.as_deref() == Some(uid)
+            && event.source.as_ref().map(|s| &s.name) == source.as_ref()
+    }) else {
+        return HashMap::new();
+    };
+    let Some(start) = start_of(master) else {
+        return HashMap::new();
+    };
+
+    let timezone = master
+        .start_tzid
+        .as_deref()
+        .and_then(|tzid| calendar.timezone(tzid));
+    let mut starts: Vec<DateTime<Utc>> = master
+        .occurrences_between(start, until, timezone.as_ref())
+        .into_iter()
+        .filter_map(|occurrence| occurrence.recurrence_id.map(|id| id.to_utc()))
+        .collect();
+    starts.sort();
+    starts
+        .into_iter()
+        .enumerate()
+        .map(|(position, start)| (start, position))
+        .collect()
+}
+
+fn start_of(event: &CalendarEvent) -> Option<DateTime<Utc>> {
+    event.start_time.map(|start| start.to_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::EventTime;
+    use indoc::indoc;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse::<EventTime>().unwrap().to_utc()
+    }
+
+    fn calendar(events: &str) -> IcalCalendar {
+        let content = format!(
+            "BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:-//Test//Test//EN\n{}END:VCALENDAR\n",
+            events
+        );
+        IcalCalendar::parse_ical_content(&content).unwrap()
+    }
+
+    #[test]
+    fn test_diff_calendars() {
+        let previous = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            LOCATION:Room 1
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:review@example.com
+            DTSTART:20251202T140000Z
+            SUMMARY:Review
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:social@example.com
+            DTSTART:20251203T170000Z
+            SUMMARY:Social
+            END:VEVENT
+        "});
+        let current = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            LOCATION:Room 1
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:standup@example.com
+            RECURRENCE-ID:20251202T100000Z
+            DTSTART:20251202T110000Z
+            SUMMARY:Standup
+            LOCATION:Room 1
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:standup@example.com
+            RECURRENCE-ID:20251203T100000Z
+            DTSTART:20251203T100000Z
+            SUMMARY:Standup
+            LOCATION:Room 2
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:review@example.com
+            DTSTART:20251202T140000Z
+            SUMMARY:Review
+            STATUS:CANCELLED
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:retro@example.com
+            DTSTART:20251204T150000Z
+            SUMMARY:Retro
+            END:VEVENT
+        "});
+
+        let changes = diff_calendars(
+            &previous,
+            &current,
+            utc("20251201T120000Z"),
+            utc("20251231T000000Z"),
+        );
+        let described: Vec<String> = changes
+            .iter()
+            .map(|series| {
+                let change = &series.change;
+                let summary = change.event().summary.clone().unwrap();
+                match change {
+                    CalendarChange::Added(_) => format!("added {}", summary),
+                    CalendarChange::Rescheduled { before, .. } => format!(
+                        "rescheduled {} from {}",
+                        summary,
+                        before.start_time.unwrap()
+                    ),
+                    CalendarChange::LocationChanged { .. } => format!("moved {}", summary),
+                    CalendarChange::Cancelled(_) => format!("cancelled {}", summary),
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            described,
+            vec![
+                "rescheduled Standup from 20251202T100000Z",
+                "cancelled Review",
+                "moved Standup",
+                "cancelled Social",
+                "added Retro",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moved_series_is_rescheduled() {
+        let previous = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            END:VEVENT
+        "});
+        let current = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T113000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            END:VEVENT
+        "});
+
+        let changes = diff_calendars(
+            &previous,
+            &current,
+            utc("20251201T000000Z"),
+            utc("20251231T000000Z"),
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].occurrences, 3);
+        match &changes[0].change {
+            CalendarChange::Rescheduled { before, after } => {
+                assert_eq!(before.start_time.unwrap().to_string(), "20251201T100000Z");
+                assert_eq!(after.start_time.unwrap().to_string(), "20251201T113000Z");
+            }
+            other => panic!("unexpected change {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_moves_across_window_are_rescheduled() {
+        let previous = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:offsite@example.com
+            DTSTART:20251205T090000Z
+            SUMMARY:Offsite
+            END:VEVENT
+        "});
+        let current = calendar(indoc! {"
+            BEGIN:VEVENT
+            UID:offsite@example.com
+            DTSTART:20260305T090000Z
+            SUMMARY:Offsite
+            END:VEVENT
+        "});
+
+        let changes = diff_calendars(
+            &previous,
+            &current,
+            utc("20251201T000000Z"),
+            utc("20251231T000000Z"),
+        );
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            changes[0].change,
+            CalendarChange::Rescheduled { .. }
+        ));
+
+        let unchanged = diff_calendars(
+            &current,
+            &current,
+            utc("20251201T000000Z"),
+            utc("20251231T000000Z"),
+        );
+        assert!(unchanged.is_empty());
+    }
+}
@@ -0,0 +1,826 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::fmt;
+
+/// Prefix that marks a message as a command.
+pub const COMMAND_PREFIX: char = '!';
+
+/// What a date argument looks like, for error replies.
+const DATE_EXPECTED: &str = "a date such as 2025-12-03, today, tomorrow or friday";
+
+/// What an argument accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A positive number, e.g. `5`
+    Count,
+    /// A day: `2025-12-03`, `today`, `tomorrow`, `friday` or `next friday`
+    Date,
+    /// A day, or two days joined by `..` or `to`
+    DateRange,
+    /// The rest of the message; quotes group words but are not required, and
+    /// keep a word that names a calendar from being read as one
+    Text,
+    /// Any number of configured calendar names
+    Calendars,
+}
+
+/// An argument in a command's signature.
+#[derive(Debug, Clone)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+}
+
+/// A command the bot understands: its name, aliases and arguments.
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: Vec<&'static str>,
+    pub summary: &'static str,
+    pub args: Vec<ArgSpec>,
+    /// Example invocations shown by `!help <command>`
+    pub examples: Vec<&'static str>,
+}
+
+impl CommandSpec {
+    pub fn new(name: &'static str, summary: &'static str) -> Self {
+        Self {
+            name,
+            aliases: Vec::new(),
+            summary,
+            args: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    /// Another name the command answers to.
+    pub fn alias(mut self, alias: &'static str) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+
+    /// An argument that must be given.
+    pub fn arg(mut self, name: &'static str, kind: ArgKind) -> Self {
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            required: true,
+        });
+        self
+    }
+
+    /// An argument that may be left out. Optional arguments are matched in
+    /// order; a word that does not fit one is tried against the next.
+    pub fn optional_arg(mut self, name: &'static str, kind: ArgKind) -> Self {
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            required: false,
+        });
+        self
+    }
+
+    /// An example invocation, including the prefix.
+    pub fn example(mut self, example: &'static str) -> Self {
+        self.examples.push(example);
+        self
+    }
+
+    /// Whether the command is called `name`, by its name or an alias.
+    pub fn answers_to(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+
+    /// Usage line, e.g. `!meetings [count] [calendar...]`.
+    pub fn usage(&self) -> String {
+        let mut usage = format!("{}{}", COMMAND_PREFIX, self.name);
+        for arg in &self.args {
+            let name = match arg.kind {
+                ArgKind::Calendars => format!("{}...", arg.name),
+                _ => arg.name.to_string(),
+            };
+            if arg.required {
+                usage.push_str(&format!(" <{}>", name));
+            } else {
+                usage.push_str(&format!(" [{}]", name));
+            }
+        }
+        usage
+    }
+
+    /// Markdown help for this command: summary, usage, aliases and examples.
+    pub fn help(&self) -> String {
+        let mut help = format!(
+            "# {}{}\n\n{}\n\nUsage: `{}`\n",
+            COMMAND_PREFIX,
+            self.name,
+            self.summary,
+            self.usage()
+        );
+        if !self.aliases.is_empty() {
+            let aliases: Vec<String> = self
+                .aliases
+                .iter()
+                .map(|alias| format!("`{}{}`", COMMAND_PREFIX, alias))
+                .collect();
+            help.push_str(&format!("\nAlso: {}\n", aliases.join(", ")));
+        }
+        if !self.examples.is_empty() {
+            help.push_str("\nExamples:\n");
+            for example in &self.examples {
+                help.push_str(&format!("* `{}`\n", example));
+            }
+        }
+        help
+    }
+}
+
+/// A parsed argument value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgValue {
+    Count(usize),
+    Date(NaiveDate),
+    /// First and last day, inclusive
+    DateRange(NaiveDate, NaiveDate),
+    Text(String),
+    Calendars(Vec<String>),
+}
+
+/// A command parsed from a message, with its arguments by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedCommand {
+    /// Name of the command, even if it was called by an alias
+    pub name: &'static str,
+    pub args: Vec<(&'static str, ArgValue)>,
+}
+
+impl ParsedCommand {
+    pub fn arg(&self, name: &str) -> Option<&ArgValue> {
+        self.args
+            .iter()
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn count(&self, name: &str) -> Option<usize> {
+        match self.arg(name) {
+            Some(ArgValue::Count(count)) => Some(*count),
+            _ => None,
+        }
+    }
+
+    /// A date or date range argument, as its first and last day.
+    pub fn date_range(&self, name: &str) -> Option<(NaiveDate, NaiveDate)> {
+        match self.arg(name) {
+            Some(ArgValue::Date(date)) => Some((*date, *date)),
+            Some(ArgValue::DateRange(first, last)) => Some((*first, *last)),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.arg(name) {
+            Some(ArgValue::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Calendar names given to the command; empty if none were.
+    pub fn calendars(&self) -> &[String] {
+        self.args
+            .iter()
+            .find_map(|(_, value)| match value {
+                ArgValue::Calendars(names) => Some(names.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Why a message could not be parsed as a command. The message is meant to
+/// be sent back to the user as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    UnknownCommand(String),
+    UnterminatedQuote,
+    MissingArgument {
+        usage: String,
+        argument: &'static str,
+    },
+    InvalidArgument {
+        usage: String,
+        argument: &'static str,
+        value: String,
+        expected: String,
+    },
+    UnexpectedArgument {
+        usage: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::UnknownCommand(name) => {
+                write!(f, "Unknown command: {}{}", COMMAND_PREFIX, name)
+            }
+            CommandError::UnterminatedQuote => write!(f, "Unterminated quote in command"),
+            CommandError::MissingArgument { usage, argument } => {
+                write!(f, "Missing {}. Usage: {}", argument, usage)
+            }
+            CommandError::InvalidArgument {
+                usage,
+                argument,
+                value,
+                expected,
+            } => write!(
+                f,
+                "Invalid {} '{}': expected {}. Usage: {}",
+                argument, value, expected, usage
+            ),
+            CommandError::UnexpectedArgument { usage, value } => {
+                write!(f, "Unexpected argument '{}'. Usage: {}", value, usage)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// What arguments are checked against when parsing.
+pub struct ParseContext<'a> {
+    /// Day that relative dates such as `tomorrow` count from
+    pub today: NaiveDate,
+    /// Names of the configured calendars
+    pub calendars: &'a [&'a str],
+}
+
+/// The commands the bot understands, looked up by name or alias.
+#[derive(Debug, Clone, Default)]
+pub struct CommandRegistry {
+    commands: Vec<CommandSpec>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a command.
+    pub fn register(mut self, command: CommandSpec) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    pub fn commands(&self) -> &[CommandSpec] {
+        &self.commands
+    }
+
+    /// Find a command by its name or an alias, with or without the prefix.
+    pub fn find(&self, name: &str) -> Option<&CommandSpec> {
+        let name = name.strip_prefix(COMMAND_PREFIX).unwrap_or(name);
+        let name = name.to_lowercase();
+        self.commands.iter().find(|c| c.answers_to(&name))
+    }
+
+    /// Markdown list of every command with its usage.
+    pub fn help(&self) -> String {
+        let mut help = String::from("# Commands\n\n");
+        for command in &self.commands {
+            help.push_str(&format!("* `{}` - {}\n", command.usage(), command.summary));
+        }
+        help.push_str(&format!(
+            "\nSend `{}help <command>` for details and examples.\n",
+            COMMAND_PREFIX
+        ));
+        help
+    }
+
+    /// Help for one command, or for all of them if no name is given.
+    pub fn help_for(&self, name: Option<&str>) -> String {
+        match name {
+            None => self.help(),
+            Some(name) => match self.find(name) {
+                Some(command) => command.help(),
+                None => format!(
+                    "Unknown command: {}{}. Send {}help to list commands.",
+                    COMMAND_PREFIX,
+                    name.trim_start_matches(COMMAND_PREFIX),
+                    COMMAND_PREFIX
+                ),
+            },
+        }
+    }
+
+    /// Parse a message. Returns `None` if the message is not a command at all.
+    pub fn parse(
+        &self,
+        message: &str,
+        context: &ParseContext,
+    ) -> Option<Result<ParsedCommand, CommandError>> {
+        let rest = message.trim().strip_prefix(COMMAND_PREFIX)?;
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(self.parse_command(rest, context))
+    }
+
+    fn parse_command(
+        &self,
+        message: &str,
+        context: &ParseContext,
+    ) -> Result<ParsedCommand, CommandError> {
+        let words = split_words(message)?;
+        let (name, words) = words.split_first().expect("command has a name");
+        let command = self
+            .find(&name.text)
+            .ok_or_else(|| CommandError::UnknownCommand(name.text.to_lowercase()))?;
+
+        let mut args = Vec::new();
+        let mut position = 0;
+        for arg in &command.args {
+            match parse_arg(arg, &words[position..], context) {
+                Ok(Some((value, consumed))) => {
+                    args.push((arg.name, value));
+                    position += consumed;
+                }
+                Ok(None) if !arg.required => {}
+                Ok(None) => {
+                    return Err(CommandError::MissingArgument {
+                        usage: command.usage(),
+                        argument: arg.name,
+                    });
+                }
+                // Optional arguments let the word fall through to the next one
+                Err(_) if !arg.required => {}
+                Err(expected) => {
+                    return Err(CommandError::InvalidArgument {
+                        usage: command.usage(),
+                        argument: arg.name,
+                        value: words[position].text.clone(),
+                        expected,
+                    });
+                }
+            }
+        }
+
+        if let Some(extra) = words.get(position) {
+            // Report why the word did not fit, if some argument could have taken it
+            if let Some(arg) = command
+                .args
+                .iter()
+                .find(|arg| !args.iter().any(|(name, _)| *name == arg.name))
+                && let Err(expected) = parse_arg(arg, &words[position..], context)
+            {
+                return Err(CommandError::InvalidArgument {
+                    usage: command.usage(),
+                    argument: arg.name,
+                    value: extra.text.clone(),
+                    expected,
+                });
+            }
+            return Err(CommandError::UnexpectedArgument {
+                usage: command.usage(),
+                value: extra.text.clone(),
+            });
+        }
+
+        Ok(ParsedCommand {
+            name: command.name,
+            args,
+        })
+    }
+}
+
+/// A word of a command, with its quotes removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Word {
+    text: String,
+    /// Whether any of the word was quoted; quoted words are always text
+    quoted: bool,
+}
+
+/// Split a message into words, keeping double-quoted text together.
+fn split_words(message: &str) -> Result<Vec<Word>, CommandError> {
+    let mut words = Vec::new();
+    let mut word = Word::default();
+    let mut quoted = false;
+    let mut in_word = false;
+
+    for c in message.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.quoted = true;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.text.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quoted {
+        return Err(CommandError::UnterminatedQuote);
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Parse an argument from the start of the remaining words. Returns the value
+/// and how many words it took, `None` if there are no words left, or what
+/// was expected if the words do not fit.
+fn parse_arg(
+    arg: &ArgSpec,
+    words: &[Word],
+    context: &ParseContext,
+) -> Result<Option<(ArgValue, usize)>, String> {
+    let Some(first) = words.first() else {
+        return Ok(None);
+    };
+    let texts: Vec<String> = words.iter().map(|word| word.text.clone()).collect();
+
+    match arg.kind {
+        ArgKind::Count => match first.text.parse::<usize>() {
+            Ok(count) if count > 0 => Ok(Some((ArgValue::Count(count), 1))),
+            _ => Err("a positive number".to_string()),
+        },
+        ArgKind::Date => parse_date(&texts, context.today)
+            .map(|(date, consumed)| Some((ArgValue::Date(date), consumed)))
+            .ok_or_else(|| DATE_EXPECTED.to_string()),
+        ArgKind::DateRange => parse_date_range(&texts, context.today)
+            .map(|(first, last, consumed)| {
+                let value = if first == last {
+                    ArgValue::Date(first)
+                } else {
+                    ArgValue::DateRange(first, last)
+                };
+                Some((value, consumed))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "{}, or a range such as 2025-12-01..2025-12-07",
+                    DATE_EXPECTED
+                )
+            }),
+        ArgKind::Text => Ok(Some((ArgValue::Text(texts.join(" ")), words.len()))),
+        ArgKind::Calendars => {
+            let names: Vec<String> = words
+                .iter()
+                .take_while(|word| !word.quoted && context.calendars.contains(&word.text.as_str()))
+                .map(|word| word.text.clone())
+                .collect();
+            if names.is_empty() {
+                return Err(format!(
+                    "a calendar name ({})",
+                    context.calendars.join(", ")
+                ));
+            }
+            let consumed = names.len();
+            Ok(Some((ArgValue::Calendars(names), consumed)))
+        }
+    }
+}
+
+/// Parse a date from the start of the words, returning it and how many words
+/// it took.
+fn parse_date(words: &[String], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let first = words.first()?.to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&first, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    match first.as_str() {
+        "today" => return Some((today, 1)),
+        "tomorrow" => return Some((today + Duration::days(1), 1)),
+        "yesterday" => return Some((today - Duration::days(1), 1)),
+        _ => {}
+    }
+
+    // "friday" is today or the coming friday; "next friday" is after today
+    if first == "next" {
+        let weekday = words.get(1)?.parse::<Weekday>().ok()?;
+        return Some((next_weekday(today + Duration::days(1), weekday), 2));
+    }
+    let weekday = first.parse::<Weekday>().ok()?;
+    Some((next_weekday(today, weekday), 1))
+}
+
+/// The first day on or after `from` that falls on the weekday.
+fn next_weekday(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_ahead =
+        (7 + weekday.num_days_from_monday() - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(days_ahead as i64)
+}
+
+/// Parse a date or a range of dates (`A..B` or `A to B`) from the start of
+/// the words, returning the first and last day and how many words it took.
+fn parse_date_range(words: &[String], today: NaiveDate) -> Option<(NaiveDate, NaiveDate, usize)> {
+    let first = words.first()?;
+    if let Some((start, end)) = first.split_once("..") {
+        let (start, _) = parse_date(&[start.to_string()], today)?;
+        let (end, _) = parse_date(&[end.to_string()], today)?;
+        return (start <= end).then_some((start, end, 1));
+    }
+
+    let (start, consumed) = parse_date(words, today)?;
+    let rest = &words[consumed..];
+    if rest
+        .first()
+        .is_some_and(|word| word.eq_ignore_ascii_case("to"))
+    {
+        let (end, end_consumed) = parse_date(&rest[1..], today)?;
+        return (start <= end).then_some((start, end, consumed + 1 + end_consumed));
+    }
+    Some((start, start, consumed))
+}
+
+/// The commands the bot answers in rooms.
+pub fn bot_commands() -> CommandRegistry {
+    CommandRegistry::new()
+        .register(
+            CommandSpec::new("meeting", "Show the next meeting/event")
+                .alias("event")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!meeting")
+                .example("!event team"),
+        )
+        .register(
+            CommandSpec::new(
+                "meetings",
+                "Show upcoming meetings/events, a page at a time",
+            )
+            .alias("events")
+            .optional_arg("count", ArgKind::Count)
+            .optional_arg("calendar", ArgKind::Calendars)
+            .example("!meetings")
+            .example("!meetings 5")
+            .example("!meetings team holidays"),
+        )
+        .register(
+            CommandSpec::new("more", "Show the next page of your last !meetings listing")
+                .example("!more"),
+        )
+        .register(
+            CommandSpec::new("today", "Show today's meetings/events")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!today"),
+        )
+        .register(
+            CommandSpec::new("tomorrow", "Show tomorrow's meetings/events")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!tomorrow team"),
+        )
+        .register(
+            CommandSpec::new("week", "Show meetings/events in the next 7 days")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!week"),
+        )
+        .register(
+            CommandSpec::new("month", "Show meetings/events in the next month")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!month holidays"),
+        )
+        .register(
+            CommandSpec::new(
+                "agenda",
+                "Show meetings/events on a day or in a range of days",
+            )
+            .arg("date", ArgKind::DateRange)
+            .optional_arg("calendar", ArgKind::Calendars)
+            .example("!agenda 2025-12-03")
+            .example("!agenda next friday")
+            .example("!agenda 2025-12-01..2025-12-07 team"),
+        )
+        .register(
+            CommandSpec::new("tasks", "Show open tasks, ordered by due date")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!tasks"),
+        )
+        .register(
+            CommandSpec::new("due", "Show open tasks due within the next 7 days")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .example("!due")
+                .example("!due team"),
+        )
+        .register(
+            CommandSpec::new("search", "Find upcoming meetings/events by text")
+                .optional_arg("calendar", ArgKind::Calendars)
+                .arg("text", ArgKind::Text)
+                .example("!search release retro")
+                .example("!search team \"design review\""),
+        )
+        .register(
+            CommandSpec::new(
+                "search-all",
+                "Find upcoming and past meetings/events by text",
+            )
+            .optional_arg("calendar", ArgKind::Calendars)
+            .arg("text", ArgKind::Text)
+            .example("!search-all kickoff"),
+        )
+        .register(
+            CommandSpec::new("help", "List commands, or explain one")
+                .optional_arg("command", ArgKind::Text)
+                .example("!help")
+                .example("!help meetings"),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn registry() -> CommandRegistry {
+        bot_commands().register(
+            CommandSpec::new("top", "Show the first events")
+                .optional_arg("count", ArgKind::Count)
+                .optional_arg("calendar", ArgKind::Calendars),
+        )
+    }
+
+    fn parse(message: &str) -> Option<Result<ParsedCommand, CommandError>> {
+        let context = ParseContext {
+            // A Wednesday
+            today: date("2025-12-03"),
+            calendars: &["team", "holidays"],
+        };
+        registry().parse(message, &context)
+    }
+
+    #[test]
+    fn test_names_and_aliases() {
+        assert_eq!(parse("!events").unwrap().unwrap().name, "meetings");
+        assert_eq!(parse("  !Meeting ").unwrap().unwrap().name, "meeting");
+        assert_eq!(
+            parse("!meetingsfoo").unwrap(),
+            Err(CommandError::UnknownCommand("meetingsfoo".to_string()))
+        );
+        assert_eq!(parse("hello !meetings"), None);
+        assert_eq!(parse("! meetings"), None);
+        assert_eq!(registry().find("!event").unwrap().name, "meeting");
+    }
+
+    #[test]
+    fn test_counts_and_calendars() {
+        let command = parse("!top 5 team holidays").unwrap().unwrap();
+        assert_eq!(command.count("count"), Some(5));
+        assert_eq!(command.calendars(), ["team", "holidays"]);
+
+        // Optional arguments can be skipped
+        let command = parse("!top team").unwrap().unwrap();
+        assert_eq!(command.count("count"), None);
+        assert_eq!(command.calendars(), ["team"]);
+
+        assert_eq!(
+            parse("!top 0").unwrap().unwrap_err().to_string(),
+            "Invalid count '0': expected a positive number. Usage: !top [count] [calendar...]"
+        );
+        assert_eq!(
+            parse("!meeting 3").unwrap().unwrap_err().to_string(),
+            "Invalid calendar '3': expected a calendar name (team, holidays). Usage: !meeting [calendar...]"
+        );
+    }
+
+    #[test]
+    fn test_dates_and_ranges() {
+        let range = |message: &str| parse(message).unwrap().unwrap().date_range("date");
+
+        assert_eq!(
+            range("!agenda 2025-12-10"),
+            Some((date("2025-12-10"), date("2025-12-10")))
+        );
+        assert_eq!(
+            range("!agenda tomorrow"),
+            Some((date("2025-12-04"), date("2025-12-04")))
+        );
+        assert_eq!(
+            range("!agenda wednesday"),
+            Some((date("2025-12-03"), date("2025-12-03")))
+        );
+        assert_eq!(
+            range("!agenda next wed"),
+            Some((date("2025-12-10"), date("2025-12-10")))
+        );
+        assert_eq!(
+            range("!agenda friday to next monday"),
+            Some((date("2025-12-05"), date("2025-12-08")))
+        );
+        assert_eq!(
+            range("!agenda 2025-12-01..2025-12-07"),
+            Some((date("2025-12-01"), date("2025-12-07")))
+        );
+
+        let command = parse("!agenda today team").unwrap().unwrap();
+        assert_eq!(command.calendars(), ["team"]);
+
+        assert!(matches!(
+            parse("!agenda").unwrap(),
+            Err(CommandError::MissingArgument {
+                argument: "date",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("!agenda 2025-12-07..2025-12-01").unwrap(),
+            Err(CommandError::InvalidArgument {
+                argument: "date",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_quoted_text() {
+        let command = parse("!search \"release retro\" notes").unwrap().unwrap();
+        assert_eq!(command.text("text"), Some("release retro notes"));
+        assert!(command.calendars().is_empty());
+
+        // Leading calendar names select calendars; quotes keep a query together
+        let command = parse("!search team \"design review\"").unwrap().unwrap();
+        assert_eq!(command.calendars(), ["team"]);
+        assert_eq!(command.text("text"), Some("design review"));
+
+        // Quoting a calendar name searches for it instead
+        let command = parse("!search \"team\"").unwrap().unwrap();
+        assert!(command.calendars().is_empty());
+        assert_eq!(command.text("text"), Some("team"));
+        let command = parse("!search team \"team\" offsite").unwrap().unwrap();
+        assert_eq!(command.calendars(), ["team"]);
+        assert_eq!(command.text("text"), Some("team offsite"));
+        assert_eq!(
+            parse("!search").unwrap().unwrap_err().to_string(),
+            "Missing text. Usage: !search [calendar...] <text>"
+        );
+
+        // A quoted word is text, even if it names a calendar
+        let words = split_words("team \"team\" te\"am\"").unwrap();
+        assert_eq!(
+            words.iter().map(|word| word.quoted).collect::<Vec<_>>(),
+            vec![false, true, true]
+        );
+        assert!(words.iter().all(|word| word.text == "team"));
+        assert_eq!(
+            parse("!tasks \"team\"").unwrap().unwrap_err().to_string(),
+            "Invalid calendar 'team': expected a calendar name (team, holidays). \
+             Usage: !tasks [calendar...]"
+        );
+
+        assert_eq!(
+            parse("!search \"release").unwrap(),
+            Err(CommandError::UnterminatedQuote)
+        );
+        assert_eq!(
+            parse("!tasks team extra").unwrap().unwrap_err().to_string(),
+            "Unexpected argument 'extra'. Usage: !tasks [calendar...]"
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        let commands = bot_commands();
+        let help = commands.help_for(None);
+        for command in commands.commands() {
+            assert!(help.contains(&format!("* `{}` - {}\n", command.usage(), command.summary)));
+        }
+
+        assert_eq!(
+            commands.help_for(Some("!events")),
+            "# !meetings\n\nShow upcoming meetings/events, a page at a time\n\n\
+             Usage: `!meetings [count] [calendar...]`\n\nAlso: `!events`\n\n\
+             Examples:\n* `!meetings`\n* `!meetings 5`\n* `!meetings team holidays`\n"
+        );
+        assert_eq!(
+            commands.help_for(Some("nope")),
+            "Unknown command: !nope. Send !help to list commands."
+        );
+
+        // Examples are themselves valid commands
+        let context = ParseContext {
+            today: date("2025-12-03"),
+            calendars: &["team", "holidays"],
+        };
+        for command in commands.commands() {
+            for example in &command.examples {
+                assert_eq!(
+                    commands.parse(example, &context).unwrap().unwrap().name,
+                    command.name
+                );
+            }
+        }
+    }
+}
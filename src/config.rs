@@ -1,6 +1,45 @@
+use crate::ical::CalendarEvent;
+use crate::time::parse_duration;
 use anyhow::{Result, anyhow};
+use chrono::Duration;
+use chrono_tz::Tz;
+use std::collections::HashMap;
 use toml::Value;
 
+/// How often the calendar feed is fetched when `refresh_interval` is not set.
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::minutes(5);
+
+/// How many events `!meetings` lists when `max_events` is not set.
+const DEFAULT_MAX_EVENTS: usize = 10;
+
+/// Name of the calendar defined by the top-level `webcal` setting.
+pub const DEFAULT_CALENDAR_NAME: &str = "default";
+
+/// Colors a calendar can be labelled with in listings.
+pub const CALENDAR_COLORS: [&str; 9] = [
+    "red", "orange", "yellow", "green", "blue", "purple", "brown", "black", "white",
+];
+
+/// Configuration for a calendar feed.
+#[derive(Debug, Clone)]
+pub struct CalendarConfig {
+    /// Name used to label events and to pick the calendar in commands and reminders
+    pub name: String,
+    /// iCal/WebCal URL of the feed
+    pub url: String,
+    /// Label color, one of `CALENDAR_COLORS`
+    pub color: Option<String>,
+}
+
+/// Configuration for which calendars each room reads.
+#[derive(Debug, Clone, Default)]
+pub struct RoomCalendarsConfig {
+    /// Calendars for rooms without a binding; empty for all calendars
+    pub default: Vec<String>,
+    /// Calendars bound to a room, keyed by Matrix room ID
+    pub rooms: HashMap<String, Vec<String>>,
+}
+
 /// Configuration for bot message filtering.
 #[derive(Debug, Clone)]
 pub struct BotFilteringConfig {
@@ -12,22 +51,69 @@ pub struct BotFilteringConfig {
     pub ignored_users: Vec<String>,
 }
 
+/// Configuration for which events are listed.
+#[derive(Debug, Clone)]
+pub struct EventFilteringConfig {
+    /// Whether to list events with STATUS:TENTATIVE
+    pub show_tentative: bool,
+    /// Whether to list events with STATUS:CANCELLED (struck through)
+    pub show_cancelled: bool,
+}
+
+/// Configuration for what is included in event listings.
+#[derive(Debug, Clone)]
+pub struct DisplayConfig {
+    /// Whether to show the organizer and attendee replies
+    pub show_attendance: bool,
+    /// Whether to show the title and details of CLASS:PRIVATE/CONFIDENTIAL events
+    pub show_private_details: bool,
+    /// How many upcoming events are listed at once; `!more` shows the next ones
+    pub max_events: usize,
+    /// Zone event times are shown in; by default each event's own TZID
+    pub timezone: Option<Tz>,
+}
+
+/// Configuration for notifications driven by VALARM components.
+#[derive(Debug, Clone, Default)]
+pub struct AlarmConfig {
+    /// Matrix room ID where alarms are announced; alarms are ignored if unset
+    pub matrix_room: Option<String>,
+}
+
+/// Configuration for announcing changes to the calendar.
+#[derive(Debug, Clone)]
+pub struct ChangeNotificationConfig {
+    /// Matrix room IDs subscribed to change announcements
+    pub matrix_rooms: Vec<String>,
+    /// How often the calendar is checked for changes
+    pub poll_interval: Duration,
+}
+
 /// Reminder type for scheduled notifications.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ReminderType {
     NextMeeting,
     AllUpcomingMeetings,
+    DueTasks,
+    /// Sent the given time before each event starts, instead of on a cron
+    BeforeEvent(Duration),
+    /// Sent when each event starts, with its join link
+    EventStart,
 }
 
 /// Configuration for a scheduled reminder.
 #[derive(Debug, Clone)]
 pub struct ReminderConfig {
-    /// Cron expression for when to send reminder
-    pub cron: String,
+    /// Cron expression for when to send reminder; unset for event-relative reminders
+    pub cron: Option<String>,
     /// Type of reminder to send
     pub reminder_type: ReminderType,
     /// Matrix room ID where to send the reminder
     pub matrix_room: String,
+    /// Whether to mention @room so everyone in the room is notified
+    pub mention_room: bool,
+    /// Names of the calendars the reminder covers; empty for all of them
+    pub calendars: Vec<String>,
 }
 
 impl Default for BotFilteringConfig {
@@ -40,6 +126,35 @@ impl Default for BotFilteringConfig {
     }
 }
 
+impl Default for ChangeNotificationConfig {
+    fn default() -> Self {
+        Self {
+            matrix_rooms: Vec::new(),
+            poll_interval: Duration::minutes(5),
+        }
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            show_attendance: false,
+            show_private_details: false,
+            max_events: DEFAULT_MAX_EVENTS,
+            timezone: None,
+        }
+    }
+}
+
+impl Default for EventFilteringConfig {
+    fn default() -> Self {
+        Self {
+            show_tentative: true,
+            show_cancelled: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub homeserver: String,
@@ -47,10 +162,18 @@ pub struct Config {
     pub access_token: String,
     pub log_file: String,
     pub working_dir: String,
-    pub webcal: String,
+    /// Calendar feeds, from `[[calendars]]` or the single `webcal` URL
+    pub calendars: Vec<CalendarConfig>,
+    pub room_calendars: RoomCalendarsConfig,
+    /// How often the calendar feed is fetched into the cache
+    pub refresh_interval: Duration,
     pub info_url: Option<String>,
     pub reminders: Vec<ReminderConfig>,
     pub bot_filtering: BotFilteringConfig,
+    pub event_filtering: EventFilteringConfig,
+    pub display: DisplayConfig,
+    pub alarms: AlarmConfig,
+    pub change_notifications: ChangeNotificationConfig,
 }
 
 impl Config {
@@ -58,6 +181,8 @@ impl Config {
         let config: Value =
             toml::from_str(toml_str).map_err(|e| anyhow!("Failed to parse TOML: {}", e))?;
 
+        let calendars = parse_calendars_config(&config)?;
+
         Ok(Config {
             homeserver: config
                 .get("homeserver")
@@ -84,17 +209,19 @@ impl Config {
                 .and_then(|v| v.as_str())
                 .unwrap_or(".")
                 .to_string(),
-            webcal: config
-                .get("webcal")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
+            reminders: parse_reminders_config(&config, &calendars)?,
+            room_calendars: parse_room_calendars_config(&config, &calendars)?,
+            calendars,
+            refresh_interval: parse_refresh_interval(&config)?,
             info_url: config
                 .get("info_url")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
-            reminders: parse_reminders_config(&config)?,
             bot_filtering: parse_bot_filtering_config(&config)?,
+            event_filtering: parse_event_filtering_config(&config)?,
+            display: parse_display_config(&config)?,
+            alarms: parse_alarms_config(&config)?,
+            change_notifications: parse_change_notifications_config(&config)?,
         })
     }
 
@@ -112,23 +239,59 @@ impl Config {
         );
         println!("  Log File: {}", self.log_file);
         println!("  Working Directory: {}", self.working_dir);
-        println!("  Webcal: {}", self.webcal);
+        println!("  Calendars:");
+        if self.calendars.is_empty() {
+            println!("    [none]");
+        } else {
+            for calendar in &self.calendars {
+                println!(
+                    "    {}: {}{}",
+                    calendar.name,
+                    calendar.url,
+                    match &calendar.color {
+                        Some(color) => format!(" ({})", color),
+                        None => String::new(),
+                    }
+                );
+            }
+        }
+        println!(
+            "  Refresh Interval: {}s",
+            self.refresh_interval.num_seconds()
+        );
         match &self.info_url {
             Some(url) => println!("  Info URL: {}", url),
             None => println!("  Info URL: [not set]"),
         }
+        println!("  Room Calendars:");
+        if self.room_calendars.default.is_empty() {
+            println!("    Default: [all]");
+        } else {
+            println!("    Default: {}", self.room_calendars.default.join(", "));
+        }
+        for (room, calendars) in &self.room_calendars.rooms {
+            println!("    {}: {}", room, calendars.join(", "));
+        }
         println!("  Reminders:");
         if self.reminders.is_empty() {
             println!("    [none]");
         } else {
             for (i, reminder) in self.reminders.iter().enumerate() {
                 println!(
-                    "    {}: {} -> {:?} in room {}",
+                    "    {}: {} -> {:?} in room {}{}",
                     i + 1,
-                    reminder.cron,
+                    reminder.cron.as_deref().unwrap_or("[event-relative]"),
                     reminder.reminder_type,
-                    reminder.matrix_room
+                    reminder.matrix_room,
+                    if reminder.mention_room {
+                        " (mentions @room)"
+                    } else {
+                        ""
+                    }
                 );
+                if !reminder.calendars.is_empty() {
+                    println!("       Calendars: {}", reminder.calendars.join(", "));
+                }
             }
         }
         println!("  Bot Filtering:");
@@ -142,11 +305,131 @@ impl Config {
         } else {
             println!("    Ignored Users: [none]");
         }
+        println!("  Event Filtering:");
+        println!(
+            "    Show Tentative: {}",
+            self.event_filtering.show_tentative
+        );
+        println!(
+            "    Show Cancelled: {}",
+            self.event_filtering.show_cancelled
+        );
+        println!("  Display:");
+        println!("    Show Attendance: {}", self.display.show_attendance);
+        println!(
+            "    Show Private Details: {}",
+            self.display.show_private_details
+        );
+        println!("    Max Events: {}", self.display.max_events);
+        match &self.display.timezone {
+            Some(timezone) => println!("    Time Zone: {}", timezone),
+            None => println!("    Time Zone: each event's own"),
+        }
+        match &self.alarms.matrix_room {
+            Some(room) => println!("  Alarms: announced in room {}", room),
+            None => println!("  Alarms: [disabled]"),
+        }
+        println!("  Change Notifications:");
+        if self.change_notifications.matrix_rooms.is_empty() {
+            println!("    [disabled]");
+        } else {
+            for room in &self.change_notifications.matrix_rooms {
+                println!("    Room: {}", room);
+            }
+            println!(
+                "    Poll Interval: {}s",
+                self.change_notifications.poll_interval.num_seconds()
+            );
+        }
+    }
+}
+
+/// Parse the calendar refresh interval from TOML value.
+fn parse_refresh_interval(config: &Value) -> Result<Duration> {
+    match config.get("refresh_interval").and_then(|v| v.as_str()) {
+        Some(interval) => parse_offset(interval, "refresh_interval"),
+        None => Ok(DEFAULT_REFRESH_INTERVAL),
+    }
+}
+
+/// Parse calendar feeds from TOML value. A top-level `webcal` URL is
+/// treated as a single calendar named `default`.
+fn parse_calendars_config(config: &Value) -> Result<Vec<CalendarConfig>> {
+    let webcal = config
+        .get("webcal")
+        .and_then(|v| v.as_str())
+        .filter(|url| !url.is_empty());
+
+    let Some(calendars_array) = config.get("calendars").and_then(|v| v.as_array()) else {
+        // No calendars section, use the webcal URL if there is one
+        return Ok(webcal
+            .map(|url| CalendarConfig {
+                name: DEFAULT_CALENDAR_NAME.to_string(),
+                url: url.to_string(),
+                color: None,
+            })
+            .into_iter()
+            .collect());
+    };
+
+    if webcal.is_some() {
+        return Err(anyhow!("Use either 'webcal' or [[calendars]], not both"));
+    }
+
+    let mut calendars: Vec<CalendarConfig> = Vec::new();
+    for calendar_value in calendars_array {
+        let name = calendar_value
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'name' in calendar configuration"))?
+            .to_string();
+
+        // Names are typed in commands and used in cache file names
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow!(
+                "Invalid calendar name '{}': use letters, digits, '-' and '_'",
+                name
+            ));
+        }
+        if calendars.iter().any(|c| c.name == name) {
+            return Err(anyhow!("Duplicate calendar name: {}", name));
+        }
+
+        let url = calendar_value
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'url' in calendar '{}'", name))?
+            .to_string();
+
+        let color = calendar_value
+            .get("color")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_lowercase());
+        if let Some(color) = &color
+            && !CALENDAR_COLORS.contains(&color.as_str())
+        {
+            return Err(anyhow!(
+                "Invalid color '{}' for calendar '{}', expected one of: {}",
+                color,
+                name,
+                CALENDAR_COLORS.join(", ")
+            ));
+        }
+
+        calendars.push(CalendarConfig { name, url, color });
     }
+
+    Ok(calendars)
 }
 
 /// Parse reminders configuration from TOML value.
-fn parse_reminders_config(config: &Value) -> Result<Vec<ReminderConfig>> {
+fn parse_reminders_config(
+    config: &Value,
+    calendars: &[CalendarConfig],
+) -> Result<Vec<ReminderConfig>> {
     let reminders_config = config.get("reminders");
 
     if let Some(reminders_array) = reminders_config.and_then(|v| v.as_array()) {
@@ -154,12 +437,6 @@ fn parse_reminders_config(config: &Value) -> Result<Vec<ReminderConfig>> {
 
         for reminder_value in reminders_array {
             if let Some(reminder_table) = reminder_value.as_table() {
-                let cron = reminder_table
-                    .get("cron")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow!("Missing 'cron' in reminder configuration"))?
-                    .to_string();
-
                 let reminder_type_str = reminder_table
                     .get("reminder_type")
                     .and_then(|v| v.as_str())
@@ -168,19 +445,52 @@ fn parse_reminders_config(config: &Value) -> Result<Vec<ReminderConfig>> {
                 let reminder_type = match reminder_type_str {
                     "NextMeeting" => ReminderType::NextMeeting,
                     "AllUpcomingMeetings" => ReminderType::AllUpcomingMeetings,
+                    "DueTasks" => ReminderType::DueTasks,
+                    "BeforeEvent" => {
+                        let before = reminder_table
+                            .get("before")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| {
+                                anyhow!("Missing 'before' in BeforeEvent reminder configuration")
+                            })?;
+                        ReminderType::BeforeEvent(parse_offset(before, "before")?)
+                    }
+                    "EventStart" => ReminderType::EventStart,
                     _ => return Err(anyhow!("Invalid reminder_type: {}", reminder_type_str)),
                 };
 
+                // Event-relative reminders are timed by the calendar, not a cron
+                let cron = match reminder_type {
+                    ReminderType::BeforeEvent(_) | ReminderType::EventStart => None,
+                    _ => Some(
+                        reminder_table
+                            .get("cron")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| anyhow!("Missing 'cron' in reminder configuration"))?
+                            .to_string(),
+                    ),
+                };
+
                 let matrix_room = reminder_table
                     .get("matrix_room")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow!("Missing 'matrix_room' in reminder configuration"))?
                     .to_string();
 
+                let mention_room = reminder_table
+                    .get("mention_room")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                let reminder_calendars =
+                    parse_calendar_names(reminder_table.get("calendars"), calendars)?;
+
                 reminders.push(ReminderConfig {
                     cron,
                     reminder_type,
                     matrix_room,
+                    mention_room,
+                    calendars: reminder_calendars,
                 });
             }
         }
@@ -192,6 +502,100 @@ fn parse_reminders_config(config: &Value) -> Result<Vec<ReminderConfig>> {
     }
 }
 
+/// Parse room calendar bindings from TOML value.
+fn parse_room_calendars_config(
+    config: &Value,
+    calendars: &[CalendarConfig],
+) -> Result<RoomCalendarsConfig> {
+    let Some(bindings) = config.get("room_calendars").and_then(|v| v.as_table()) else {
+        // No room_calendars section, every room reads all calendars
+        return Ok(RoomCalendarsConfig::default());
+    };
+
+    let mut room_calendars = RoomCalendarsConfig::default();
+    for (key, value) in bindings {
+        let names = parse_calendar_names(Some(value), calendars)?;
+        if key == "default" {
+            room_calendars.default = names;
+        } else {
+            room_calendars.rooms.insert(key.clone(), names);
+        }
+    }
+
+    Ok(room_calendars)
+}
+
+/// Parse a list of calendar names, checking each is configured.
+fn parse_calendar_names(
+    value: Option<&Value>,
+    calendars: &[CalendarConfig],
+) -> Result<Vec<String>> {
+    let names: Vec<String> = value
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !calendars.iter().any(|c| &c.name == *name))
+    {
+        return Err(anyhow!("Unknown calendar: {}", unknown));
+    }
+    Ok(names)
+}
+
+/// Parse a positive duration such as `10m`, `1h30m`, `2d` or `PT10M`, used
+/// for reminder offsets and intervals. `field` names the setting in errors.
+fn parse_offset(value: &str, field: &str) -> Result<Duration> {
+    let invalid = || anyhow!("Invalid '{}': {}", field, value);
+    let trimmed = value.trim();
+
+    if trimmed
+        .trim_start_matches(['+', '-'])
+        .starts_with(['P', 'p'])
+    {
+        return positive(parse_duration(trimmed).map_err(|_| invalid())?, field);
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in trimmed.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        total += match c.to_ascii_lowercase() {
+            'd' => Duration::try_days(n),
+            'h' => Duration::try_hours(n),
+            'm' => Duration::try_minutes(n),
+            's' => Duration::try_seconds(n),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+    }
+
+    if !number.is_empty() || trimmed.is_empty() {
+        return Err(invalid());
+    }
+    positive(total, field)
+}
+
+/// Reject zero and negative durations, e.g. a `-PT10M` reminder that would
+/// fire after the event started.
+fn positive(duration: Duration, field: &str) -> Result<Duration> {
+    if duration <= Duration::zero() {
+        return Err(anyhow!("'{}' must be positive", field));
+    }
+    Ok(duration)
+}
+
 /// Parse bot filtering configuration from TOML value.
 fn parse_bot_filtering_config(config: &Value) -> Result<BotFilteringConfig> {
     let bot_filtering_config = config.get("bot_filtering");
@@ -232,6 +636,160 @@ fn parse_bot_filtering_config(config: &Value) -> Result<BotFilteringConfig> {
     }
 }
 
+/// Parse event filtering configuration from TOML value.
+fn parse_event_filtering_config(config: &Value) -> Result<EventFilteringConfig> {
+    let defaults = EventFilteringConfig::default();
+
+    if let Some(filter_config) = config.get("event_filtering") {
+        let show_tentative = filter_config
+            .get("show_tentative")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.show_tentative);
+
+        let show_cancelled = filter_config
+            .get("show_cancelled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.show_cancelled);
+
+        Ok(EventFilteringConfig {
+            show_tentative,
+            show_cancelled,
+        })
+    } else {
+        // No event_filtering section, use defaults
+        Ok(defaults)
+    }
+}
+
+/// Parse display configuration from TOML value.
+fn parse_display_config(config: &Value) -> Result<DisplayConfig> {
+    let defaults = DisplayConfig::default();
+
+    if let Some(display_config) = config.get("display") {
+        let show_attendance = display_config
+            .get("show_attendance")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.show_attendance);
+
+        let show_private_details = display_config
+            .get("show_private_details")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.show_private_details);
+
+        let max_events = match display_config
+            .get("max_events")
+            .and_then(|v| v.as_integer())
+        {
+            Some(max_events) if max_events > 0 => max_events as usize,
+            Some(_) => return Err(anyhow!("'max_events' must be positive")),
+            None => defaults.max_events,
+        };
+
+        let timezone = match display_config.get("timezone").and_then(|v| v.as_str()) {
+            Some(name) => Some(
+                name.parse::<Tz>()
+                    .map_err(|_| anyhow!("Invalid display timezone: {}", name))?,
+            ),
+            None => defaults.timezone,
+        };
+
+        Ok(DisplayConfig {
+            show_attendance,
+            show_private_details,
+            max_events,
+            timezone,
+        })
+    } else {
+        // No display section, use defaults
+        Ok(defaults)
+    }
+}
+
+/// Parse alarms configuration from TOML value.
+fn parse_alarms_config(config: &Value) -> Result<AlarmConfig> {
+    if let Some(alarms_config) = config.get("alarms") {
+        let matrix_room = alarms_config
+            .get("matrix_room")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        Ok(AlarmConfig { matrix_room })
+    } else {
+        // No alarms section, alarms are not announced
+        Ok(AlarmConfig::default())
+    }
+}
+
+/// Parse change notifications configuration from TOML value.
+fn parse_change_notifications_config(config: &Value) -> Result<ChangeNotificationConfig> {
+    let defaults = ChangeNotificationConfig::default();
+
+    if let Some(changes_config) = config.get("change_notifications") {
+        let matrix_rooms = changes_config
+            .get("matrix_rooms")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let poll_interval = match changes_config.get("poll_interval").and_then(|v| v.as_str()) {
+            Some(interval) => parse_offset(interval, "poll_interval")?,
+            None => defaults.poll_interval,
+        };
+
+        Ok(ChangeNotificationConfig {
+            matrix_rooms,
+            poll_interval,
+        })
+    } else {
+        // No change_notifications section, changes are not announced
+        Ok(defaults)
+    }
+}
+
+/// Check if an event should be listed based on event filtering configuration.
+pub fn should_show_event(event: &CalendarEvent, config: &EventFilteringConfig) -> bool {
+    if event.is_cancelled() {
+        return config.show_cancelled;
+    }
+
+    if event.is_tentative() {
+        return config.show_tentative;
+    }
+
+    true
+}
+
+/// Calendars a room reads: its own binding, or the default. Empty means all.
+pub fn calendars_for_room<'a>(room_id: &str, config: &'a RoomCalendarsConfig) -> &'a [String] {
+    config.rooms.get(room_id).unwrap_or(&config.default)
+}
+
+/// Calendars a reminder covers: its own list, or those of its room.
+pub fn calendars_for_reminder<'a>(
+    reminder: &'a ReminderConfig,
+    config: &'a RoomCalendarsConfig,
+) -> &'a [String] {
+    if reminder.calendars.is_empty() {
+        calendars_for_room(&reminder.matrix_room, config)
+    } else {
+        &reminder.calendars
+    }
+}
+
+/// Check if an event comes from one of the named calendars; an empty list
+/// means all. Events from unmerged calendars carry no source and always match.
+pub fn event_in_calendars(event: &CalendarEvent, calendars: &[String]) -> bool {
+    match &event.source {
+        Some(source) if !calendars.is_empty() => calendars.contains(&source.name),
+        _ => true,
+    }
+}
+
 /// Check if a user ID should be ignored based on bot filtering configuration.
 pub fn should_ignore_user(user_id: &str, bot_user_id: &str, config: &BotFilteringConfig) -> bool {
     // Check if it's bot itself
@@ -275,13 +833,27 @@ mod tests {
         assert_eq!(config.access_token, "secret_token");
         assert_eq!(config.log_file, "bot.log");
         assert_eq!(config.working_dir, ".");
-        assert_eq!(config.webcal, "");
+        assert!(config.calendars.is_empty());
+        assert_eq!(config.refresh_interval, Duration::minutes(5));
         assert_eq!(config.info_url, None);
         assert!(config.reminders.is_empty());
         // Bot filtering should use defaults when not specified
         assert!(config.bot_filtering.ignore_self);
         assert!(!config.bot_filtering.ignore_bots);
         assert!(config.bot_filtering.ignored_users.is_empty());
+        // Event filtering should hide only cancelled events by default
+        assert!(config.event_filtering.show_tentative);
+        assert!(!config.event_filtering.show_cancelled);
+        assert!(!config.display.show_attendance);
+        assert!(!config.display.show_private_details);
+        assert_eq!(config.display.max_events, 10);
+        assert_eq!(config.display.timezone, None);
+        assert_eq!(config.alarms.matrix_room, None);
+        assert!(config.change_notifications.matrix_rooms.is_empty());
+        assert_eq!(
+            config.change_notifications.poll_interval,
+            Duration::minutes(5)
+        );
     }
 
     #[test]
@@ -294,6 +866,7 @@ mod tests {
             log_file = \"/var/log/bot.log\"
             working_directory = \"/app\"
             webcal = \"https://example.com/calendar.ics\"
+            refresh_interval = \"2m\"
             info_url = \"https://example.com/info\"
 
             [[reminders]]
@@ -306,10 +879,42 @@ mod tests {
             reminder_type = \"AllUpcomingMeetings\"
             matrix_room = \"!roomid:example.com\"
 
+            [[reminders]]
+            cron = \"0 0 9 * * 5\"
+            reminder_type = \"DueTasks\"
+            matrix_room = \"!roomid:example.com\"
+
+            [[reminders]]
+            reminder_type = \"BeforeEvent\"
+            before = \"10m\"
+            matrix_room = \"!roomid:example.com\"
+
+            [[reminders]]
+            reminder_type = \"EventStart\"
+            matrix_room = \"!roomid:example.com\"
+            mention_room = true
+
             [bot_filtering]
             ignore_self = false
             ignore_bots = true
             ignored_users = [\"@spam-bot:example.com\", \"@announcement-bot:example.com\"]
+
+            [event_filtering]
+            show_tentative = false
+            show_cancelled = true
+
+            [display]
+            show_attendance = true
+            show_private_details = true
+            max_events = 5
+            timezone = \"Europe/Berlin\"
+
+            [alarms]
+            matrix_room = \"!alarms:example.com\"
+
+            [change_notifications]
+            matrix_rooms = [\"!roomid:example.com\", \"!other:example.com\"]
+            poll_interval = \"15m\"
         "};
 
         // When parsing the TOML configuration
@@ -321,21 +926,34 @@ mod tests {
         assert_eq!(config.access_token, "secret_token");
         assert_eq!(config.log_file, "/var/log/bot.log");
         assert_eq!(config.working_dir, "/app");
-        assert_eq!(config.webcal, "https://example.com/calendar.ics");
+        assert_eq!(config.calendars.len(), 1);
+        assert_eq!(config.calendars[0].name, DEFAULT_CALENDAR_NAME);
+        assert_eq!(config.calendars[0].url, "https://example.com/calendar.ics");
+        assert_eq!(config.refresh_interval, Duration::minutes(2));
         assert_eq!(
             config.info_url,
             Some("https://example.com/info".to_string())
         );
-        assert_eq!(config.reminders.len(), 2);
-        assert_eq!(config.reminders[0].cron, "0 9 * * 1-5");
+        assert_eq!(config.reminders.len(), 5);
+        assert_eq!(config.reminders[0].cron.as_deref(), Some("0 9 * * 1-5"));
         assert_eq!(config.reminders[0].reminder_type, ReminderType::NextMeeting);
         assert_eq!(config.reminders[0].matrix_room, "!roomid:example.com");
-        assert_eq!(config.reminders[1].cron, "0 8 * * 1");
+        assert_eq!(config.reminders[1].cron.as_deref(), Some("0 8 * * 1"));
         assert_eq!(
             config.reminders[1].reminder_type,
             ReminderType::AllUpcomingMeetings
         );
         assert_eq!(config.reminders[1].matrix_room, "!roomid:example.com");
+        assert_eq!(config.reminders[2].reminder_type, ReminderType::DueTasks);
+        assert_eq!(config.reminders[3].cron, None);
+        assert_eq!(
+            config.reminders[3].reminder_type,
+            ReminderType::BeforeEvent(Duration::minutes(10))
+        );
+        assert!(!config.reminders[3].mention_room);
+        assert_eq!(config.reminders[4].cron, None);
+        assert_eq!(config.reminders[4].reminder_type, ReminderType::EventStart);
+        assert!(config.reminders[4].mention_room);
         assert!(!config.bot_filtering.ignore_self);
         assert!(config.bot_filtering.ignore_bots);
         assert_eq!(config.bot_filtering.ignored_users.len(), 2);
@@ -351,6 +969,21 @@ mod tests {
                 .ignored_users
                 .contains(&"@announcement-bot:example.com".to_string())
         );
+        assert!(!config.event_filtering.show_tentative);
+        assert!(config.event_filtering.show_cancelled);
+        assert!(config.display.show_attendance);
+        assert!(config.display.show_private_details);
+        assert_eq!(config.display.max_events, 5);
+        assert_eq!(config.display.timezone, Some(chrono_tz::Europe::Berlin));
+        assert_eq!(
+            config.alarms.matrix_room,
+            Some("!alarms:example.com".to_string())
+        );
+        assert_eq!(config.change_notifications.matrix_rooms.len(), 2);
+        assert_eq!(
+            config.change_notifications.poll_interval,
+            Duration::minutes(15)
+        );
     }
 
     #[test]
@@ -419,6 +1052,187 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_before_event_reminder_requires_offset() {
+        // Given a BeforeEvent reminder without a 'before' offset
+        let toml_str = indoc! {"
+            homeserver = \"https://matrix.example.com\"
+            username = \"@bot:example.com\"
+            access_token = \"secret_token\"
+
+            [[reminders]]
+            reminder_type = \"BeforeEvent\"
+            matrix_room = \"!roomid:example.com\"
+        "};
+
+        // When parsing the TOML configuration
+        let result = Config::from_toml(toml_str);
+
+        // Then it should return an error indicating the missing field
+        assert!(result.unwrap_err().to_string().contains("Missing 'before'"));
+    }
+
+    /// Two named calendars, and a reminder limited to one of them.
+    const NAMED_CALENDARS: &str = indoc! {"
+        homeserver = \"https://matrix.example.com\"
+        username = \"@bot:example.com\"
+        access_token = \"secret_token\"
+
+        [[calendars]]
+        name = \"team\"
+        url = \"https://example.com/team.ics\"
+        color = \"Blue\"
+
+        [[calendars]]
+        name = \"holidays\"
+        url = \"https://example.com/holidays.ics\"
+
+        [[reminders]]
+        cron = \"0 9 * * 1-5\"
+        reminder_type = \"NextMeeting\"
+        matrix_room = \"!roomid:example.com\"
+        calendars = [\"team\"]
+    "};
+
+    #[test]
+    fn test_named_calendars() {
+        // Given a configuration with named calendars
+        let toml_str = NAMED_CALENDARS;
+
+        // When parsing the TOML configuration
+        let config = Config::from_toml(toml_str).unwrap();
+
+        // Then the calendars should keep their order and normalized colors
+        let names: Vec<_> = config.calendars.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["team", "holidays"]);
+        assert_eq!(config.calendars[0].color.as_deref(), Some("blue"));
+        assert_eq!(config.calendars[1].color, None);
+
+        // And the reminder should be limited to its calendar
+        assert_eq!(config.reminders[0].calendars, vec!["team"]);
+    }
+
+    #[test]
+    fn test_invalid_named_calendars_error() {
+        // Given configurations with an unknown reminder calendar, an unknown
+        // color, a duplicate name, and both `webcal` and `[[calendars]]`
+        let invalid = [
+            NAMED_CALENDARS.replace("calendars = [\"team\"]", "calendars = [\"other\"]"),
+            NAMED_CALENDARS.replace("Blue", "teal"),
+            NAMED_CALENDARS.replace("holidays\"\n", "team\"\n"),
+            format!(
+                "webcal = \"https://example.com/calendar.ics\"\n{}",
+                NAMED_CALENDARS
+            ),
+        ];
+
+        for toml_str in &invalid {
+            // When parsing the TOML configuration
+            let result = Config::from_toml(toml_str);
+
+            // Then it should return an error
+            assert!(result.is_err(), "{}", toml_str);
+        }
+    }
+
+    /// Calendars bound to one room, with a default for the others.
+    const ROOM_CALENDARS: &str = indoc! {"
+        homeserver = \"https://matrix.example.com\"
+        username = \"@bot:example.com\"
+        access_token = \"secret_token\"
+
+        [[calendars]]
+        name = \"team\"
+        url = \"https://example.com/team.ics\"
+
+        [[calendars]]
+        name = \"project-a\"
+        url = \"https://example.com/project-a.ics\"
+
+        [[reminders]]
+        cron = \"0 9 * * 1-5\"
+        reminder_type = \"NextMeeting\"
+        matrix_room = \"!projecta:example.com\"
+
+        [room_calendars]
+        default = [\"team\"]
+        \"!projecta:example.com\" = [\"project-a\", \"team\"]
+    "};
+
+    #[test]
+    fn test_room_calendars() {
+        // Given a configuration binding a room to its own calendars
+        let toml_str = ROOM_CALENDARS;
+
+        // When parsing the TOML configuration
+        let config = Config::from_toml(toml_str).unwrap();
+        let rooms = &config.room_calendars;
+
+        // Then the bound room should read its calendars and others the default
+        assert_eq!(
+            calendars_for_room("!projecta:example.com", rooms),
+            ["project-a", "team"]
+        );
+        assert_eq!(calendars_for_room("!other:example.com", rooms), ["team"]);
+
+        // And reminders to the room should follow its binding
+        assert_eq!(
+            calendars_for_reminder(&config.reminders[0], rooms),
+            ["project-a", "team"]
+        );
+    }
+
+    #[test]
+    fn test_room_calendars_unknown_calendar_error() {
+        // Given a room binding that names an unknown calendar
+        let toml_str = ROOM_CALENDARS.replace("default = [\"team\"]", "default = [\"other\"]");
+
+        // When parsing the TOML configuration
+        let result = Config::from_toml(&toml_str);
+
+        // Then it should return an error naming the calendar
+        assert!(result.unwrap_err().to_string().contains("other"));
+    }
+
+    #[test]
+    fn test_rooms_read_all_calendars_by_default() {
+        // Given a configuration without room bindings
+        let toml_str = ROOM_CALENDARS.replace("[room_calendars]", "[unused]");
+
+        // When parsing the TOML configuration
+        let config = Config::from_toml(&toml_str).unwrap();
+
+        // Then rooms should not be limited to any calendar
+        assert!(calendars_for_room("!projecta:example.com", &config.room_calendars).is_empty());
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        // Given offsets in the short and the iCal form
+        let parse = |value| parse_offset(value, "before");
+
+        // When they are parsed
+        // Then they add up to positive durations
+        assert_eq!(parse("10m").unwrap(), Duration::minutes(10));
+        assert_eq!(parse("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse("2d").unwrap(), Duration::days(2));
+        assert_eq!(parse("PT15M").unwrap(), Duration::minutes(15));
+        assert_eq!(parse("+PT15M").unwrap(), Duration::minutes(15));
+
+        // And malformed, zero and negative offsets are rejected, naming the setting
+        assert_eq!(parse("10").unwrap_err().to_string(), "Invalid 'before': 10");
+        assert!(parse("ten minutes").is_err());
+        assert!(parse("").is_err());
+        assert_eq!(
+            parse("0m").unwrap_err().to_string(),
+            "'before' must be positive"
+        );
+        assert_eq!(
+            parse("-PT10M").unwrap_err().to_string(),
+            "'before' must be positive"
+        );
+    }
+
     #[test]
     fn test_should_ignore_user_self_filtering() {
         // Given bot filtering config with ignore_self = true
@@ -497,4 +1311,49 @@ mod tests {
         assert!(should_ignore_user(uppercase_bot_id, bot_user_id, &config));
         assert!(should_ignore_user(mixed_case_bot_id, bot_user_id, &config));
     }
+
+    #[test]
+    fn test_should_show_event_by_status() {
+        // Given events with each status
+        let calendar = crate::ical::IcalCalendar::parse_ical_content(indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Confirmed
+            DTSTART:20251201T100000Z
+            STATUS:CONFIRMED
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Tentative
+            DTSTART:20251201T100000Z
+            STATUS:TENTATIVE
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Cancelled
+            DTSTART:20251201T100000Z
+            STATUS:CANCELLED
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let events = &calendar.events;
+
+        // When using the default filtering
+        let config = EventFilteringConfig::default();
+
+        // Then cancelled events should be hidden
+        assert!(should_show_event(&events[0], &config));
+        assert!(should_show_event(&events[1], &config));
+        assert!(!should_show_event(&events[2], &config));
+
+        // When tentative events are hidden and cancelled events are shown
+        let config = EventFilteringConfig {
+            show_tentative: false,
+            show_cancelled: true,
+        };
+
+        // Then only tentative events should be hidden
+        assert!(should_show_event(&events[0], &config));
+        assert!(!should_show_event(&events[1], &config));
+        assert!(should_show_event(&events[2], &config));
+    }
 }
@@ -1,19 +1,205 @@
+use crate::alarm::{AlarmTrigger, EventAlarm};
+use crate::recurrence::RecurrenceRule;
+use crate::time::{EventTime, parse_duration};
+use crate::timezone::{CalendarTimeZone, VTimeZone};
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use ical::parser::ical::IcalParser;
+use ical::property::Property;
+use std::collections::HashMap;
 use std::fs;
 use std::io::BufReader;
 
+/// How far ahead recurring events are expanded when a query has no max date.
+const DEFAULT_EXPANSION_DAYS: i64 = 366;
+
+/// Overall status of an event (the STATUS property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Tentative,
+    Confirmed,
+    Cancelled,
+}
+
+/// Whether an event blocks time on a calendar (the TRANSP property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transparency {
+    Opaque,
+    Transparent,
+}
+
+/// Access classification of an event (the CLASS property).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    Public,
+    Private,
+    Confidential,
+}
+
+/// An attendee's reply to an invitation (the PARTSTAT parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticipationStatus {
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+/// An attendee's part in an event (the ROLE parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticipantRole {
+    Chair,
+    Required,
+    Optional,
+    NonParticipant,
+}
+
+/// The organizer or an attendee of an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Participant {
+    /// Calendar address, usually a `mailto:` URI
+    pub address: String,
+    /// Common name (the CN parameter)
+    pub name: Option<String>,
+    pub status: ParticipationStatus,
+    pub role: ParticipantRole,
+}
+
+impl Participant {
+    fn from_property(property: &Property) -> Option<Self> {
+        let address = property.value.clone()?;
+
+        let status = match param(property, "PARTSTAT").map(str::to_uppercase) {
+            Some(v) if v == "ACCEPTED" => ParticipationStatus::Accepted,
+            Some(v) if v == "DECLINED" => ParticipationStatus::Declined,
+            Some(v) if v == "TENTATIVE" => ParticipationStatus::Tentative,
+            Some(v) if v == "DELEGATED" => ParticipationStatus::Delegated,
+            _ => ParticipationStatus::NeedsAction,
+        };
+
+        let role = match param(property, "ROLE").map(str::to_uppercase) {
+            Some(v) if v == "CHAIR" => ParticipantRole::Chair,
+            Some(v) if v == "OPT-PARTICIPANT" => ParticipantRole::Optional,
+            Some(v) if v == "NON-PARTICIPANT" => ParticipantRole::NonParticipant,
+            _ => ParticipantRole::Required,
+        };
+
+        Some(Participant {
+            address,
+            name: param(property, "CN").map(|s| s.to_string()),
+            status,
+            role,
+        })
+    }
+
+    /// The common name, or the address without its `mailto:` prefix.
+    pub fn display_name(&self) -> &str {
+        if let Some(name) = &self.name {
+            return name;
+        }
+        self.address
+            .strip_prefix("mailto:")
+            .or_else(|| self.address.strip_prefix("MAILTO:"))
+            .unwrap_or(&self.address)
+    }
+}
+
+/// An event found by [`IcalCalendar::search`] and how well it matched.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub event: CalendarEvent,
+    /// Higher is a better match
+    pub score: u32,
+}
+
+/// The named calendar an event or task came from, when several are merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSource {
+    pub name: String,
+    /// Color name used to tell calendars apart in listings, e.g. `blue`
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct CalendarEvent {
     pub summary: Option<String>,
     pub description: Option<String>,
-    pub start_time: Option<String>,
-    pub end_time: Option<String>,
+    /// DTSTART; times with a resolvable TZID are converted to instants
+    pub start_time: Option<EventTime>,
+    /// DTEND; times with a resolvable TZID are converted to instants
+    pub end_time: Option<EventTime>,
+    /// TZID of DTSTART, kept so recurrences are expanded in local time
+    pub start_tzid: Option<String>,
     pub location: Option<String>,
     pub url: Option<String>,
+    /// Unique identifier shared by all occurrences of an event
+    pub uid: Option<String>,
+    /// Original start of the occurrence this event represents or overrides
+    pub recurrence_id: Option<EventTime>,
+    /// Raw RRULE value for recurring events
+    pub rrule: Option<String>,
+    /// Additional occurrence starts (RDATE)
+    pub rdates: Vec<EventTime>,
+    /// Excluded occurrence starts (EXDATE)
+    pub exdates: Vec<EventTime>,
+    pub status: Option<EventStatus>,
+    pub transparency: Option<Transparency>,
+    pub organizer: Option<Participant>,
+    pub attendees: Vec<Participant>,
+    pub categories: Vec<String>,
+    /// PRIORITY from 1 (highest) to 9 (lowest); 0 or absent means undefined
+    pub priority: Option<u8>,
+    pub classification: Option<Classification>,
+    /// GEO as (latitude, longitude)
+    pub geo: Option<(f64, f64)>,
+    /// ATTACH values, usually URIs
+    pub attachments: Vec<String>,
+    /// Non-standard X- properties keyed by name; later values replace earlier ones
+    pub custom_properties: HashMap<String, String>,
+    /// VALARM components; alarms that cannot be parsed are skipped
+    pub alarms: Vec<EventAlarm>,
+    /// Calendar the event came from; only set on merged calendars
+    pub source: Option<EventSource>,
+}
+
+/// Progress of a task (the STATUS property of a VTODO).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    NeedsAction,
+    InProcess,
+    Completed,
+    Cancelled,
+}
+
+/// A to-do item (VTODO) from the calendar.
+#[derive(Debug, Clone)]
+pub struct CalendarTask {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub uid: Option<String>,
+    pub url: Option<String>,
+    /// DTSTART; times with a resolvable TZID are converted to instants
+    pub start_time: Option<EventTime>,
+    /// DUE; times with a resolvable TZID are converted to instants
+    pub due: Option<EventTime>,
+    /// When the task was completed (COMPLETED)
+    pub completed: Option<EventTime>,
+    /// PRIORITY from 1 (highest) to 9 (lowest); 0 or absent means undefined
+    pub priority: Option<u8>,
+    pub status: Option<TaskStatus>,
+    /// PERCENT-COMPLETE from 0 to 100
+    pub percent_complete: Option<u8>,
+    pub categories: Vec<String>,
+    /// Calendar the task came from; only set on merged calendars
+    pub source: Option<EventSource>,
 }
 
 pub struct IcalCalendar {
     pub events: Vec<CalendarEvent>,
+    pub tasks: Vec<CalendarTask>,
+    /// VTIMEZONE definitions embedded in the calendar, keyed by TZID
+    pub timezones: HashMap<String, VTimeZone>,
 }
 
 impl IcalCalendar {
@@ -40,23 +226,67 @@ impl IcalCalendar {
         Self::parse_ical_content(&content)
     }
 
-    fn parse_ical_content(content: &str) -> Result<Self> {
+    pub fn parse_ical_content(content: &str) -> Result<Self> {
         let reader = BufReader::new(content.as_bytes());
         let parser = IcalParser::new(reader);
 
         let mut events = Vec::new();
+        let mut tasks = Vec::new();
+        let mut timezones = HashMap::new();
 
         for calendar_result in parser {
             match calendar_result {
                 Ok(calendar) => {
+                    // Definitions that cannot be parsed are skipped; their TZIDs
+                    // may still resolve through the IANA database
+                    for timezone in &calendar.timezones {
+                        if let Ok(def) = VTimeZone::from_ical(timezone) {
+                            timezones.insert(def.tzid.clone(), def);
+                        }
+                    }
+
+                    // Floating times are interpreted in the calendar's default zone
+                    let default_tzid = calendar
+                        .properties
+                        .iter()
+                        .find(|p| p.name == "X-WR-TIMEZONE")
+                        .and_then(|p| p.value.clone());
+                    let zones = ZoneContext {
+                        timezones: &timezones,
+                        default_tzid: default_tzid.as_deref(),
+                    };
+
                     for event in calendar.events {
+                        let mut duration = None;
                         let mut calendar_event = CalendarEvent {
                             summary: None,
                             description: None,
                             start_time: None,
                             end_time: None,
+                            start_tzid: None,
                             location: None,
                             url: None,
+                            uid: None,
+                            recurrence_id: None,
+                            rrule: None,
+                            rdates: Vec::new(),
+                            exdates: Vec::new(),
+                            status: None,
+                            transparency: None,
+                            organizer: None,
+                            attendees: Vec::new(),
+                            categories: Vec::new(),
+                            priority: None,
+                            classification: None,
+                            geo: None,
+                            attachments: Vec::new(),
+                            custom_properties: HashMap::new(),
+                            alarms: event
+                                .alarms
+                                .iter()
+                                .filter_map(|alarm| EventAlarm::from_ical(alarm).ok())
+                                .collect(),
+                            source: None,
                         };
 
                         for property in event.properties {
@@ -68,10 +298,23 @@ impl IcalCalendar {
                                     calendar_event.description = property.value.clone();
                                 }
                                 "DTSTART" => {
-                                    calendar_event.start_time = property.value.clone();
+                                    calendar_event.start_time = zones.value(&property);
+                                    if matches!(
+                                        calendar_event.start_time,
+                                        Some(EventTime::Instant(_))
+                                    ) {
+                                        calendar_event.start_tzid =
+                                            zones.tzid(&property).map(|s| s.to_string());
+                                    }
                                 }
                                 "DTEND" => {
-                                    calendar_event.end_time = property.value.clone();
+                                    calendar_event.end_time = zones.value(&property);
+                                }
+                                "DURATION" => {
+                                    duration = property
+                                        .value
+                                        .as_deref()
+                                        .and_then(|v| parse_duration(v).ok());
                                 }
                                 "LOCATION" => {
                                     calendar_event.location = property.value.clone();
@@ -79,12 +322,151 @@ impl IcalCalendar {
                                 "URL" => {
                                     calendar_event.url = property.value.clone();
                                 }
+                                "UID" => {
+                                    calendar_event.uid = property.value.clone();
+                                }
+                                "RECURRENCE-ID" => {
+                                    calendar_event.recurrence_id = zones.value(&property);
+                                }
+                                "RRULE" => {
+                                    calendar_event.rrule = property.value.clone();
+                                }
+                                "RDATE" => {
+                                    calendar_event.rdates.extend(zones.list(&property));
+                                }
+                                "EXDATE" => {
+                                    calendar_event.exdates.extend(zones.list(&property));
+                                }
+                                "STATUS" => {
+                                    calendar_event.status = match property
+                                        .value
+                                        .as_deref()
+                                        .map(str::to_uppercase)
+                                    {
+                                        Some(v) if v == "TENTATIVE" => Some(EventStatus::Tentative),
+                                        Some(v) if v == "CONFIRMED" => Some(EventStatus::Confirmed),
+                                        Some(v) if v == "CANCELLED" => Some(EventStatus::Cancelled),
+                                        _ => None,
+                                    };
+                                }
+                                "TRANSP" => {
+                                    calendar_event.transparency =
+                                        match property.value.as_deref().map(str::to_uppercase) {
+                                            Some(v) if v == "OPAQUE" => Some(Transparency::Opaque),
+                                            Some(v) if v == "TRANSPARENT" => {
+                                                Some(Transparency::Transparent)
+                                            }
+                                            _ => None,
+                                        };
+                                }
+                                "ORGANIZER" => {
+                                    calendar_event.organizer =
+                                        Participant::from_property(&property);
+                                }
+                                "ATTENDEE" => {
+                                    calendar_event
+                                        .attendees
+                                        .extend(Participant::from_property(&property));
+                                }
+                                "CATEGORIES" => {
+                                    calendar_event.categories.extend(categories(&property));
+                                }
+                                "PRIORITY" => {
+                                    calendar_event.priority = priority(&property);
+                                }
+                                "CLASS" => {
+                                    calendar_event.classification = match property
+                                        .value
+                                        .as_deref()
+                                        .map(str::to_uppercase)
+                                    {
+                                        Some(v) if v == "PUBLIC" => Some(Classification::Public),
+                                        Some(v) if v == "PRIVATE" => Some(Classification::Private),
+                                        Some(v) if v == "CONFIDENTIAL" => {
+                                            Some(Classification::Confidential)
+                                        }
+                                        _ => None,
+                                    };
+                                }
+                                "GEO" => {
+                                    calendar_event.geo = property.value.as_deref().and_then(|v| {
+                                        let (lat, lon) = v.split_once(';')?;
+                                        Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+                                    });
+                                }
+                                "ATTACH" => {
+                                    calendar_event.attachments.extend(property.value.clone());
+                                }
+                                name if name.starts_with("X-") => {
+                                    if let Some(value) = &property.value {
+                                        calendar_event
+                                            .custom_properties
+                                            .insert(name.to_string(), value.clone());
+                                    }
+                                }
                                 _ => {}
                             }
                         }
 
+                        calendar_event.end_time = calendar_event.derived_end(duration);
                         events.push(calendar_event);
                     }
+
+                    for todo in calendar.todos {
+                        let mut task = CalendarTask {
+                            summary: None,
+                            description: None,
+                            uid: None,
+                            url: None,
+                            start_time: None,
+                            due: None,
+                            completed: None,
+                            priority: None,
+                            status: None,
+                            percent_complete: None,
+                            categories: Vec::new(),
+                            source: None,
+                        };
+
+                        for property in todo.properties {
+                            match property.name.as_str() {
+                                "SUMMARY" => task.summary = property.value.clone(),
+                                "DESCRIPTION" => task.description = property.value.clone(),
+                                "UID" => task.uid = property.value.clone(),
+                                "URL" => task.url = property.value.clone(),
+                                "DTSTART" => task.start_time = zones.value(&property),
+                                "DUE" => task.due = zones.value(&property),
+                                "COMPLETED" => task.completed = zones.value(&property),
+                                "PRIORITY" => task.priority = priority(&property),
+                                "STATUS" => {
+                                    task.status = match property
+                                        .value
+                                        .as_deref()
+                                        .map(str::to_uppercase)
+                                    {
+                                        Some(v) if v == "NEEDS-ACTION" => {
+                                            Some(TaskStatus::NeedsAction)
+                                        }
+                                        Some(v) if v == "IN-PROCESS" => Some(TaskStatus::InProcess),
+                                        Some(v) if v == "COMPLETED" => Some(TaskStatus::Completed),
+                                        Some(v) if v == "CANCELLED" => Some(TaskStatus::Cancelled),
+                                        _ => None,
+                                    };
+                                }
+                                "PERCENT-COMPLETE" => {
+                                    task.percent_complete = property
+                                        .value
+                                        .as_deref()
+                                        .and_then(|v| v.trim().parse().ok())
+                                        .filter(|p| *p <= 100);
+                                }
+                                "CATEGORIES" => task.categories.extend(categories(&property)),
+                                _ => {}
+                            }
+                        }
+
+                        tasks.push(task);
+                    }
                 }
                 Err(e) => {
                     return Err(anyhow!("Failed to parse iCal: {}", e));
@@ -92,54 +474,227 @@ impl IcalCalendar {
             }
         }
 
-        Ok(IcalCalendar { events })
+        Ok(IcalCalendar {
+            events,
+            tasks,
+            timezones,
+        })
+    }
+
+    /// Combine several calendars into one, tagging every event and task with
+    /// the calendar it came from. Where calendars define the same VTIMEZONE,
+    /// the first definition is kept.
+    pub fn merge<'a>(
+        calendars: impl IntoIterator<Item = (&'a EventSource, &'a IcalCalendar)>,
+    ) -> Self {
+        let mut merged = IcalCalendar {
+            events: Vec::new(),
+            tasks: Vec::new(),
+            timezones: HashMap::new(),
+        };
+
+        for (source, calendar) in calendars {
+            merged
+                .events
+                .extend(calendar.events.iter().map(|event| CalendarEvent {
+                    source: Some(source.clone()),
+                    ..event.clone()
+                }));
+            merged
+                .tasks
+                .extend(calendar.tasks.iter().map(|task| CalendarTask {
+                    source: Some(source.clone()),
+                    ..task.clone()
+                }));
+            for (tzid, timezone) in &calendar.timezones {
+                merged
+                    .timezones
+                    .entry(tzid.clone())
+                    .or_insert_with(|| timezone.clone());
+            }
+        }
+
+        merged
+    }
+
+    /// Resolve a TZID against the IANA database and this calendar's VTIMEZONEs.
+    pub fn timezone(&self, tzid: &str) -> Option<CalendarTimeZone> {
+        CalendarTimeZone::resolve(tzid, &self.timezones)
+    }
+
+    /// Expand all events into concrete occurrences starting within the window
+    /// (inclusive at both ends).
+    ///
+    /// Recurring events yield one event per occurrence. Occurrences that have
+    /// a RECURRENCE-ID override in the calendar are replaced by it.
+    pub fn occurrences_between(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<CalendarEvent> {
+        let overrides: Vec<&CalendarEvent> = self
+            .events
+            .iter()
+            .filter(|event| event.recurrence_id.is_some())
+            .collect();
+
+        let mut occurrences: Vec<CalendarEvent> = self
+            .events
+            .iter()
+            .filter(|event| event.recurrence_id.is_none())
+            .flat_map(|event| {
+                let timezone = event.start_tzid.as_deref().and_then(|t| self.timezone(t));
+                event.occurrences_between(window_start, window_end, timezone.as_ref())
+            })
+            .filter(|occurrence| {
+                !overrides.iter().any(|o| {
+                    o.uid == occurrence.uid
+                        && o.source == occurrence.source
+                        && o.overrides(occurrence)
+                })
+            })
+            .collect();
+
+        occurrences.extend(overrides.into_iter().cloned());
+        occurrences.retain(|event| event.starts_within(window_start, window_end));
+        occurrences
+    }
+
+    /// Events (or occurrences) in progress at the given time, i.e. that have
+    /// started and not yet ended, sorted by start time.
+    pub fn get_current_events(&self, current_time: DateTime<Utc>) -> Vec<CalendarEvent> {
+        // Look back far enough to catch the longest event that may still be running
+        let lookback = self.longest_duration();
+
+        let mut current_events: Vec<CalendarEvent> = self
+            .occurrences_between(current_time - lookback, current_time)
+            .into_iter()
+            .filter(|event| event.is_in_progress(current_time))
+            .collect();
+        sort_by_start(&mut current_events);
+        current_events
+    }
+
+    /// Events (or occurrences) that overlap the window: they start before its
+    /// end and end after its start. Sorted by start time, so events already
+    /// running when the window opens come first.
+    pub fn get_events_between(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<CalendarEvent> {
+        let lookback = self.longest_duration();
+
+        let mut events: Vec<CalendarEvent> = self
+            .occurrences_between(window_start - lookback, window_end)
+            .into_iter()
+            .filter(|event| event.overlaps(window_start, window_end))
+            .collect();
+        sort_by_start(&mut events);
+        events
+    }
+
+    /// Events (or occurrences) matching every word of the query in their
+    /// summary, categories, location or description, ignoring case.
+    ///
+    /// Events starting after `after` are searched, and with `include_past`
+    /// also those that started in the year before. Results are ranked by
+    /// score, then upcoming before past, then by how close to `after` they
+    /// start, so the next occurrence of a recurring match comes first.
+    pub fn search(
+        &self,
+        query: &str,
+        after: DateTime<Utc>,
+        include_past: bool,
+    ) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut events = self.get_upcoming_events(after);
+        if include_past {
+            let since = after - Duration::days(DEFAULT_EXPANSION_DAYS);
+            events.extend(
+                self.occurrences_between(since, after)
+                    .into_iter()
+                    .filter(|event| event.start_time.is_some_and(|s| s.to_utc() <= after)),
+            );
+            // Like upcoming events, one-off events are not bounded by the window
+            events.extend(
+                self.events
+                    .iter()
+                    .filter(|event| !event.is_recurring() && event.recurrence_id.is_none())
+                    .filter(|event| event.start_time.is_some_and(|s| s.to_utc() < since))
+                    .cloned(),
+            );
+        }
+
+        let mut results: Vec<SearchResult> = events
+            .into_iter()
+            .filter_map(|event| {
+                let score = event.search_score(&terms)?;
+                Some(SearchResult { event, score })
+            })
+            .collect();
+        results.sort_by_key(|result| {
+            let start = result.event.start_time.map(|start| start.to_utc());
+            let past = start.is_none_or(|start| start <= after);
+            let distance = start.map(|start| (start - after).abs());
+            (std::cmp::Reverse(result.score), past, distance)
+        });
+        results
+    }
+
+    /// Duration of the longest event, used to look back for events that
+    /// started earlier and are still running.
+    fn longest_duration(&self) -> Duration {
+        self.events
+            .iter()
+            .filter_map(|event| event.duration())
+            .max()
+            .unwrap_or_else(Duration::zero)
     }
 
-    pub fn get_upcoming_events(&self, current_time: &str) -> Vec<&CalendarEvent> {
+    pub fn get_upcoming_events(&self, current_time: DateTime<Utc>) -> Vec<CalendarEvent> {
         self.get_upcoming_events_limited(current_time, None)
     }
 
     pub fn get_upcoming_events_limited(
         &self,
-        current_time: &str,
+        current_time: DateTime<Utc>,
         limit: Option<usize>,
-    ) -> Vec<&CalendarEvent> {
+    ) -> Vec<CalendarEvent> {
         self.get_upcoming_events_filtered(current_time, None, limit)
     }
 
     pub fn get_upcoming_events_filtered(
         &self,
-        current_time: &str,
-        max_date: Option<&str>,
+        current_time: DateTime<Utc>,
+        max_date: Option<DateTime<Utc>>,
         limit: Option<usize>,
-    ) -> Vec<&CalendarEvent> {
-        let mut upcoming_events: Vec<&CalendarEvent> = self
-            .events
-            .iter()
-            .filter(|event| {
-                if let Some(start_time) = &event.start_time {
-                    let start_time_str = start_time.as_str();
-                    let is_future = start_time_str > current_time;
-
-                    let is_before_max = if let Some(max_date) = max_date {
-                        start_time_str <= max_date
-                    } else {
-                        true
-                    };
+    ) -> Vec<CalendarEvent> {
+        let horizon = max_date.unwrap_or(current_time + Duration::days(DEFAULT_EXPANSION_DAYS));
+        let mut upcoming_events = self.occurrences_between(current_time, horizon);
 
-                    is_future && is_before_max
-                } else {
-                    false
-                }
-            })
-            .collect();
+        // The horizon only bounds recurrence expansion, not one-off events
+        if max_date.is_none() {
+            upcoming_events.extend(
+                self.events
+                    .iter()
+                    .filter(|event| !event.is_recurring())
+                    .filter(|event| event.start_time.is_some_and(|s| s.to_utc() > horizon))
+                    .cloned(),
+            );
+        }
 
-        upcoming_events.sort_by(|a, b| match (&a.start_time, &b.start_time) {
-            (Some(a_time), Some(b_time)) => a_time.cmp(b_time),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Equal,
+        upcoming_events.retain(|event| {
+            event
+                .start_time
+                .is_some_and(|start| start.to_utc() > current_time)
         });
+        sort_by_start(&mut upcoming_events);
 
         if let Some(limit) = limit {
             upcoming_events.truncate(limit);
@@ -147,6 +702,498 @@ impl IcalCalendar {
 
         upcoming_events
     }
+
+    /// Timed occurrences starting after `after` and no later than `until`,
+    /// sorted by start time. All-day events are skipped.
+    pub fn starts_between(&self, after: DateTime<Utc>, until: DateTime<Utc>) -> Vec<CalendarEvent> {
+        let mut starting = self.occurrences_between(after, until);
+        starting.retain(|event| {
+            !event.is_all_day() && event.start_time.is_some_and(|start| start.to_utc() > after)
+        });
+        sort_by_start(&mut starting);
+        starting
+    }
+
+    /// Alarms that go off after `after` and no later than `until`, with the
+    /// occurrence each belongs to, in trigger order.
+    pub fn alarms_between(
+        &self,
+        after: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, CalendarEvent)> {
+        let (lead, lag) = self.alarm_reach();
+        let in_window = |time: &DateTime<Utc>| *time > after && *time <= until;
+        let mut alarms: Vec<(DateTime<Utc>, CalendarEvent)> = self
+            .occurrences_between(after - lag, until + lead)
+            .into_iter()
+            .flat_map(|event| {
+                event
+                    .alarm_times(false)
+                    .into_iter()
+                    .filter(in_window)
+                    .map(move |time| (time, event.clone()))
+            })
+            .collect();
+
+        // Absolute triggers go off once whatever the occurrences, and are
+        // reported with the next occurrence of their event
+        for event in &self.events {
+            for time in event.alarm_times(true).into_iter().filter(in_window) {
+                alarms.push((time, self.next_occurrence(event, time)));
+            }
+        }
+
+        // Exceptions often copy the absolute triggers of their series
+        alarms.sort_by_key(|(time, event)| (*time, event.uid.clone()));
+        alarms.dedup_by(|(time_a, a), (time_b, b)| {
+            time_a == time_b && a.uid.is_some() && a.uid == b.uid
+        });
+        alarms
+    }
+
+    /// How long before and after an occurrence starts its relative alarms
+    /// can go off, at most, so occurrences are looked for far enough around
+    /// a window.
+    fn alarm_reach(&self) -> (Duration, Duration) {
+        let mut lead = Duration::zero();
+        let mut lag = Duration::zero();
+        for event in &self.events {
+            let Some(start) = event.start_time.map(|start| start.to_utc()) else {
+                continue;
+            };
+            for time in event.alarm_times(false) {
+                lead = lead.max(start - time);
+                lag = lag.max(time - start);
+            }
+        }
+        (lead, lag)
+    }
+
+    /// The first occurrence of a recurring event starting at or after the
+    /// given time, or the event itself.
+    fn next_occurrence(&self, event: &CalendarEvent, time: DateTime<Utc>) -> CalendarEvent {
+        if !event.is_recurring() || event.recurrence_id.is_some() {
+            return event.clone();
+        }
+        self.occurrences_between(time, time + Duration::days(DEFAULT_EXPANSION_DAYS))
+            .into_iter()
+            .filter(|occurrence| occurrence.uid == event.uid && occurrence.source == event.source)
+            .filter_map(|occurrence| Some((occurrence.start_time?.to_utc(), occurrence)))
+            .filter(|(start, _)| *start >= time)
+            .min_by_key(|(start, _)| *start)
+            .map_or_else(|| event.clone(), |(_, occurrence)| occurrence)
+    }
+
+    /// Tasks that are neither completed nor cancelled, by due date (undated
+    /// last) and then priority.
+    pub fn get_open_tasks(&self) -> Vec<CalendarTask> {
+        let mut open_tasks: Vec<CalendarTask> = self
+            .tasks
+            .iter()
+            .filter(|task| task.is_open())
+            .cloned()
+            .collect();
+        open_tasks.sort_by_key(|task| {
+            (
+                task.due.is_none(),
+                task.due.map(|due| due.to_utc()),
+                task.priority.unwrap_or(u8::MAX),
+            )
+        });
+        open_tasks
+    }
+
+    /// Open tasks due at or before the deadline, including overdue ones.
+    pub fn get_tasks_due_before(&self, deadline: DateTime<Utc>) -> Vec<CalendarTask> {
+        let mut due_tasks = self.get_open_tasks();
+        due_tasks.retain(|task| task.due.is_some_and(|due| due.to_utc() <= deadline));
+        due_tasks
+    }
+}
+
+impl CalendarTask {
+    /// Whether the task still needs doing.
+    pub fn is_open(&self) -> bool {
+        !matches!(
+            self.status,
+            Some(TaskStatus::Completed | TaskStatus::Cancelled)
+        ) && self.completed.is_none()
+            && self.percent_complete != Some(100)
+    }
+
+    /// Whether the task is open and its due time has passed.
+    pub fn is_overdue(&self, current_time: DateTime<Utc>) -> bool {
+        self.is_open() && self.due.is_some_and(|due| due.to_utc() < current_time)
+    }
+}
+
+/// Sort events by start time, earliest first.
+fn sort_by_start(events: &mut [CalendarEvent]) {
+    events.sort_by_key(|event| event.start_time.map(|start| start.to_utc()));
+}
+
+impl CalendarEvent {
+    /// Whether this event recurs through an RRULE or RDATE.
+    pub fn is_recurring(&self) -> bool {
+        self.rrule.is_some() || !self.rdates.is_empty()
+    }
+
+    /// The end of the event: DTEND if given, otherwise DTSTART plus DURATION.
+    /// All-day events without either last one day, as per RFC 5545.
+    fn derived_end(&self, duration: Option<Duration>) -> Option<EventTime> {
+        let start = self.start_time?;
+        match (self.end_time, duration) {
+            (Some(end), _) => Some(end),
+            (None, Some(duration)) => Some(start + duration),
+            (None, None) if start.is_all_day() => Some(start + Duration::days(1)),
+            (None, None) => None,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status == Some(EventStatus::Cancelled)
+    }
+
+    pub fn is_tentative(&self) -> bool {
+        self.status == Some(EventStatus::Tentative)
+    }
+
+    /// Whether the event does not block time (TRANSP:TRANSPARENT).
+    pub fn is_transparent(&self) -> bool {
+        self.transparency == Some(Transparency::Transparent)
+    }
+
+    /// Whether details should be withheld (CLASS:PRIVATE or CONFIDENTIAL).
+    pub fn is_private(&self) -> bool {
+        matches!(
+            self.classification,
+            Some(Classification::Private | Classification::Confidential)
+        )
+    }
+
+    /// Whether the event is tagged with the category, ignoring case.
+    pub fn has_category(&self, category: &str) -> bool {
+        self.categories
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category))
+    }
+
+    /// How well the event matches lowercase search terms, or `None` if some
+    /// term is not found. Each term scores by the best field it is found in
+    /// (summary, categories, location, then description), doubled when it
+    /// starts a word there; the whole query in the summary scores extra.
+    fn search_score(&self, terms: &[&str]) -> Option<u32> {
+        let fields: Vec<(String, u32)> = [
+            (self.summary.clone(), 4),
+            (Some(self.categories.join(" ")), 3),
+            (self.location.clone(), 2),
+            (self.description.clone(), 1),
+        ]
+        .into_iter()
+        .filter_map(|(text, weight)| Some((text?.to_lowercase(), weight)))
+        .collect();
+
+        let mut score = 0;
+        for term in terms {
+            score += fields
+                .iter()
+                .filter_map(|(text, weight)| {
+                    let starts_word = text
+                        .split(|c: char| !c.is_alphanumeric())
+                        .any(|word| word.starts_with(term));
+                    if starts_word {
+                        Some(weight * 2)
+                    } else {
+                        text.contains(term).then_some(*weight)
+                    }
+                })
+                .max()?;
+        }
+
+        if terms.len() > 1
+            && let Some(summary) = &self.summary
+            && summary.to_lowercase().contains(&terms.join(" "))
+        {
+            score += 4;
+        }
+        Some(score)
+    }
+
+    /// Link for joining the meeting: the URL property, or else the first web
+    /// link in the location or description (e.g. a video call link).
+    pub fn join_link(&self) -> Option<&str> {
+        self.url.as_deref().or_else(|| {
+            [&self.location, &self.description]
+                .into_iter()
+                .flatten()
+                .flat_map(|text| text.split_whitespace())
+                .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+                .map(|word| word.trim_end_matches(['.', ',', ';', ')', '>']))
+        })
+    }
+
+    /// Times at which this event's alarms go off, either those with an
+    /// absolute trigger or those relative to its start or end.
+    pub fn alarm_times(&self, absolute: bool) -> Vec<DateTime<Utc>> {
+        let start = self.start_time.map(|start| start.to_utc());
+        let end = self.end_time.map(|end| end.to_utc());
+        self.alarms
+            .iter()
+            .filter(|alarm| matches!(alarm.trigger, AlarmTrigger::Absolute(_)) == absolute)
+            .flat_map(|alarm| alarm.trigger_times(start, end))
+            .collect()
+    }
+
+    /// Number of attendees who replied with the given status.
+    pub fn attendee_count(&self, status: ParticipationStatus) -> usize {
+        self.attendees
+            .iter()
+            .filter(|attendee| attendee.status == status)
+            .count()
+    }
+
+    /// Time between start and end, if both are known.
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end_time?.to_utc() - self.start_time?.to_utc())
+    }
+
+    /// Whether the event has started but not yet ended at the given time.
+    pub fn is_in_progress(&self, current_time: DateTime<Utc>) -> bool {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => {
+                start.to_utc() <= current_time && current_time < end.to_utc()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this is an all-day (VALUE=DATE) event.
+    pub fn is_all_day(&self) -> bool {
+        self.start_time.is_some_and(|start| start.is_all_day())
+    }
+
+    /// First and last day covered by an all-day event. DTEND is exclusive,
+    /// and an all-day event without DTEND covers a single day.
+    pub fn all_day_span(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let Some(EventTime::AllDay(first_day)) = self.start_time else {
+            return None;
+        };
+        let last_day = match self.end_time {
+            Some(EventTime::AllDay(end)) if end > first_day => end.pred_opt()?,
+            _ => first_day,
+        };
+        Some((first_day, last_day))
+    }
+
+    /// Whether the event starts before `window_end` and is still running at
+    /// `window_start`. Events without an end count as instants.
+    fn overlaps(&self, window_start: DateTime<Utc>, window_end: DateTime<Utc>) -> bool {
+        let Some(start) = self.start_time.map(|start| start.to_utc()) else {
+            return false;
+        };
+        let end = self.end_time.map_or(start, |end| end.to_utc());
+        start < window_end && (start >= window_start || end > window_start)
+    }
+
+    /// Whether the event starts within the window (inclusive at both ends).
+    fn starts_within(&self, window_start: DateTime<Utc>, window_end: DateTime<Utc>) -> bool {
+        self.start_time.is_some_and(|start| {
+            let start = start.to_utc();
+            start >= window_start && start <= window_end
+        })
+    }
+
+    /// Expand this event into the occurrences starting within the window.
+    ///
+    /// The occurrence set is the RRULE instances plus RDATEs, minus EXDATEs.
+    /// Each occurrence carries its original start as `recurrence_id`. Events
+    /// that do not recur (or whose RRULE cannot be parsed) are returned
+    /// unchanged if they start within the window.
+    ///
+    /// With a time zone, the rule is expanded in local time (so occurrences
+    /// keep their wall-clock time across DST changes) and converted to UTC.
+    pub fn occurrences_between(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+        timezone: Option<&CalendarTimeZone>,
+    ) -> Vec<CalendarEvent> {
+        let Some(dtstart) = self.start_time else {
+            return Vec::new();
+        };
+        let unchanged = || {
+            if self.starts_within(window_start, window_end) {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            }
+        };
+        if !self.is_recurring() {
+            return unchanged();
+        }
+        let rule = match self.rrule.as_deref().map(str::parse::<RecurrenceRule>) {
+            Some(Ok(rule)) => Some(rule),
+            Some(Err(_)) => return unchanged(),
+            None => None,
+        };
+        let duration = self.end_time.map(|end| end.naive() - dtstart.naive());
+
+        // Expand in local wall-clock time when the event has a time zone
+        let zone = timezone.filter(|_| matches!(dtstart, EventTime::Instant(_)));
+        let local_start = match zone {
+            Some(tz) => tz.from_utc(dtstart.to_utc()),
+            None => dtstart.naive(),
+        };
+        let to_output = |local| match zone {
+            Some(tz) => EventTime::Instant(tz.to_utc(local)),
+            None => dtstart.with_naive(local),
+        };
+        let rule = rule.map(|mut rule| {
+            if let Some(tz) = zone
+                && rule.until_utc
+            {
+                rule.until = rule.until.map(|until| tz.from_utc(until.and_utc()));
+            }
+            rule
+        });
+
+        // Local times can be up to a day away from the UTC window
+        let local_end = window_end.naive_utc() + Duration::days(1);
+        let mut starts: Vec<EventTime> = match &rule {
+            Some(rule) => rule
+                .occurrences(local_start)
+                .take_while(|occurrence| *occurrence <= local_end)
+                .map(to_output)
+                .collect(),
+            None => vec![dtstart],
+        };
+        starts.extend(self.rdates.iter().map(|rdate| in_zone(*rdate, zone)));
+        starts.sort_by_key(|start| start.to_utc());
+        starts.dedup_by_key(|start| start.to_utc());
+
+        starts
+            .into_iter()
+            .filter(|start| {
+                let start = start.to_utc();
+                start >= window_start && start <= window_end
+            })
+            .filter(|start| !self.is_excluded(*start, zone))
+            .map(|start| CalendarEvent {
+                start_time: Some(start),
+                end_time: duration.map(|d| start + d),
+                recurrence_id: Some(start),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Whether this RECURRENCE-ID override replaces the given occurrence.
+    fn overrides(&self, occurrence: &CalendarEvent) -> bool {
+        match (self.recurrence_id, occurrence.recurrence_id) {
+            (Some(a), Some(b)) => a.to_utc() == b.to_utc(),
+            _ => false,
+        }
+    }
+
+    /// Whether an occurrence start is removed by an EXDATE. A DATE-only
+    /// EXDATE excludes every occurrence on that (local) day.
+    fn is_excluded(&self, start: EventTime, zone: Option<&CalendarTimeZone>) -> bool {
+        self.exdates.iter().any(|exdate| match exdate {
+            EventTime::AllDay(date) => {
+                let local = match zone {
+                    Some(tz) => tz.from_utc(start.to_utc()),
+                    None => start.naive(),
+                };
+                local.date() == *date
+            }
+            _ => in_zone(*exdate, zone).to_utc() == start.to_utc(),
+        })
+    }
+}
+
+/// Interpret an RDATE/EXDATE in the event's zone. Floating values are taken
+/// to be in DTSTART's zone, as RFC 5545 requires them to match its type.
+fn in_zone(time: EventTime, zone: Option<&CalendarTimeZone>) -> EventTime {
+    match (time, zone) {
+        (EventTime::Floating(local), Some(tz)) => EventTime::Instant(tz.to_utc(local)),
+        _ => time,
+    }
+}
+
+/// Time zones available while parsing a calendar.
+struct ZoneContext<'a> {
+    timezones: &'a HashMap<String, VTimeZone>,
+    /// X-WR-TIMEZONE of the calendar, applied to floating times
+    default_tzid: Option<&'a str>,
+}
+
+impl ZoneContext<'_> {
+    /// The effective TZID of a property: its TZID parameter or the default.
+    fn tzid<'p>(&'p self, property: &'p Property) -> Option<&'p str> {
+        param(property, "TZID").or(self.default_tzid)
+    }
+
+    /// Parse a DATE or DATE-TIME property. Floating times with a resolvable
+    /// TZID become instants; unknown zones leave the value floating.
+    fn value(&self, property: &Property) -> Option<EventTime> {
+        let value = property.value.as_deref()?.parse().ok()?;
+        Some(self.resolve(value, self.tzid(property)))
+    }
+
+    /// Like [`ZoneContext::value`], for comma-separated RDATE/EXDATE lists.
+    /// For PERIOD values only the start of the period is kept.
+    fn list(&self, property: &Property) -> Vec<EventTime> {
+        let tzid = self.tzid(property);
+        property
+            .value
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|item| item.split('/').next())
+            .filter_map(|item| item.parse().ok())
+            .map(|value| self.resolve(value, tzid))
+            .collect()
+    }
+
+    fn resolve(&self, value: EventTime, tzid: Option<&str>) -> EventTime {
+        let timezone = tzid.and_then(|tzid| CalendarTimeZone::resolve(tzid, self.timezones));
+        match (value, timezone) {
+            (EventTime::Floating(local), Some(tz)) => EventTime::Instant(tz.to_utc(local)),
+            _ => value,
+        }
+    }
+}
+
+/// Comma-separated CATEGORIES values.
+fn categories(property: &Property) -> Vec<String> {
+    property
+        .value
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_string())
+        .collect()
+}
+
+/// A PRIORITY value; 0 (undefined) and out-of-range values give `None`.
+fn priority(property: &Property) -> Option<u8> {
+    property
+        .value
+        .as_deref()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|p| (1..=9).contains(p))
+}
+
+/// Look up a property parameter value, e.g. the TZID of a DTSTART.
+fn param<'a>(property: &'a Property, name: &str) -> Option<&'a str> {
+    property
+        .params
+        .as_ref()?
+        .iter()
+        .find(|(key, _)| key == name)
+        .and_then(|(_, values)| values.first())
+        .map(|value| value.as_str())
 }
 
 #[cfg(test)]
@@ -154,6 +1201,14 @@ mod tests {
     use super::*;
     use indoc::indoc;
 
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse::<EventTime>().unwrap().to_utc()
+    }
+
+    fn ical(time: Option<EventTime>) -> Option<String> {
+        time.map(|t| t.to_string())
+    }
+
     #[test]
     fn test_parse_ical_content() {
         let ical_content = indoc! {"
@@ -187,8 +1242,14 @@ mod tests {
             Some("This is a test meeting".to_string())
         );
         assert_eq!(first_event.location, Some("Conference Room".to_string()));
-        assert_eq!(first_event.start_time, Some("20251203T100000Z".to_string()));
-        assert_eq!(first_event.end_time, Some("20251203T110000Z".to_string()));
+        assert_eq!(
+            ical(first_event.start_time),
+            Some("20251203T100000Z".to_string())
+        );
+        assert_eq!(
+            ical(first_event.end_time),
+            Some("20251203T110000Z".to_string())
+        );
 
         let second_event = &calendar.events[1];
         assert_eq!(second_event.summary, Some("Another Meeting".to_string()));
@@ -218,11 +1279,12 @@ mod tests {
         "};
 
         let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
-        let upcoming = calendar.get_upcoming_events("20251203T120000Z");
+        let upcoming = calendar.get_upcoming_events(utc("20251203T120000Z"));
         assert_eq!(upcoming.len(), 1);
         assert_eq!(upcoming[0].summary, Some("Future Event".to_string()));
 
-        let upcoming_limited = calendar.get_upcoming_events_limited("20251203T120000Z", Some(1));
+        let upcoming_limited =
+            calendar.get_upcoming_events_limited(utc("20251203T120000Z"), Some(1));
         assert_eq!(upcoming_limited.len(), 1);
         assert_eq!(
             upcoming_limited[0].summary,
@@ -265,10 +1327,11 @@ mod tests {
 
         let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
 
-        let all_upcoming = calendar.get_upcoming_events("20251203T120000Z");
+        let all_upcoming = calendar.get_upcoming_events(utc("20251203T120000Z"));
         assert_eq!(all_upcoming.len(), 3);
 
-        let limited_upcoming = calendar.get_upcoming_events_limited("20251203T120000Z", Some(2));
+        let limited_upcoming =
+            calendar.get_upcoming_events_limited(utc("20251203T120000Z"), Some(2));
         assert_eq!(limited_upcoming.len(), 2);
         assert_eq!(
             limited_upcoming[0].summary,
@@ -279,7 +1342,7 @@ mod tests {
             Some("Future Event 2".to_string())
         );
 
-        let no_limit = calendar.get_upcoming_events_limited("20251203T120000Z", None);
+        let no_limit = calendar.get_upcoming_events_limited(utc("20251203T120000Z"), None);
         assert_eq!(no_limit.len(), 3);
     }
 
@@ -312,20 +1375,20 @@ mod tests {
 
         let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
 
-        let all_upcoming = calendar.get_upcoming_events("20251203T120000Z");
+        let all_upcoming = calendar.get_upcoming_events(utc("20251203T120000Z"));
         assert_eq!(all_upcoming.len(), 2);
 
         let filtered = calendar.get_upcoming_events_filtered(
-            "20251203T120000Z",
-            Some("20251206T235959Z"),
+            utc("20251203T120000Z"),
+            Some(utc("20251206T235959Z")),
             None,
         );
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].summary, Some("Near Future Event".to_string()));
 
         let filtered_limited = calendar.get_upcoming_events_filtered(
-            "20251203T120000Z",
-            Some("20251215T235959Z"),
+            utc("20251203T120000Z"),
+            Some(utc("20251215T235959Z")),
             Some(1),
         );
         assert_eq!(filtered_limited.len(), 1);
@@ -334,4 +1397,957 @@ mod tests {
             Some("Near Future Event".to_string())
         );
     }
+
+    #[test]
+    fn test_recurring_event_expansion() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251103T090000Z
+            DTEND:20251103T091500Z
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE
+            SUMMARY:Standup
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:one-off@example.com
+            DTSTART:20251204T140000Z
+            DTEND:20251204T150000Z
+            SUMMARY:One-off
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+
+        let upcoming = calendar.get_upcoming_events_limited(utc("20251203T120000Z"), Some(3));
+        assert_eq!(upcoming.len(), 3);
+        assert_eq!(upcoming[0].summary, Some("One-off".to_string()));
+        assert_eq!(upcoming[1].summary, Some("Standup".to_string()));
+        assert_eq!(
+            ical(upcoming[1].start_time),
+            Some("20251208T090000Z".to_string())
+        );
+        assert_eq!(
+            ical(upcoming[1].end_time),
+            Some("20251208T091500Z".to_string())
+        );
+        assert_eq!(
+            ical(upcoming[2].start_time),
+            Some("20251210T090000Z".to_string())
+        );
+
+        let window = calendar.get_upcoming_events_filtered(
+            utc("20251201T000000Z"),
+            Some(utc("20251207T235959Z")),
+            None,
+        );
+        let starts: Vec<_> = window.iter().filter_map(|e| ical(e.start_time)).collect();
+        assert_eq!(
+            starts,
+            vec!["20251201T090000Z", "20251203T090000Z", "20251204T140000Z"]
+        );
+    }
+
+    #[test]
+    fn test_recurring_event_with_count_ends() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:series@example.com
+            DTSTART:20251201T100000
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Workshop Series
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let upcoming = calendar.get_upcoming_events(utc("20251201T120000"));
+        let starts: Vec<_> = upcoming.iter().filter_map(|e| ical(e.start_time)).collect();
+        assert_eq!(starts, vec!["20251202T100000", "20251203T100000"]);
+    }
+
+    #[test]
+    fn test_recurrence_exceptions_and_overrides() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:weekly@example.com
+            DTSTART:20251201T100000Z
+            DTEND:20251201T110000Z
+            RRULE:FREQ=WEEKLY;COUNT=4
+            EXDATE:20251208T100000Z
+            RDATE:20251219T150000Z
+            SUMMARY:Weekly Sync
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:weekly@example.com
+            RECURRENCE-ID:20251215T100000Z
+            DTSTART:20251216T130000Z
+            DTEND:20251216T140000Z
+            SUMMARY:Weekly Sync (moved)
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let upcoming = calendar.get_upcoming_events(utc("20251130T000000Z"));
+
+        let starts: Vec<_> = upcoming
+            .iter()
+            .map(|e| {
+                (
+                    e.start_time.unwrap().to_string(),
+                    e.summary.clone().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                ("20251201T100000Z".to_string(), "Weekly Sync".to_string()),
+                (
+                    "20251216T130000Z".to_string(),
+                    "Weekly Sync (moved)".to_string()
+                ),
+                ("20251219T150000Z".to_string(), "Weekly Sync".to_string()),
+                ("20251222T100000Z".to_string(), "Weekly Sync".to_string()),
+            ]
+        );
+        assert_eq!(upcoming[0].uid, Some("weekly@example.com".to_string()));
+        assert_eq!(
+            ical(upcoming[0].recurrence_id),
+            Some("20251201T100000Z".to_string())
+        );
+        assert_eq!(
+            ical(upcoming[2].end_time),
+            Some("20251219T160000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tzid_times_resolve_to_utc() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:berlin@example.com
+            DTSTART;TZID=Europe/Berlin:20251203T100000
+            DTEND;TZID=Europe/Berlin:20251203T110000
+            SUMMARY:Berlin Meeting
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:nyc@example.com
+            DTSTART;TZID=America/New_York:20251203T080000
+            DTEND;TZID=America/New_York:20251203T090000
+            SUMMARY:New York Meeting
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let upcoming = calendar.get_upcoming_events(utc("20251203T093000Z"));
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].summary, Some("New York Meeting".to_string()));
+        assert_eq!(
+            ical(upcoming[0].start_time),
+            Some("20251203T130000Z".to_string())
+        );
+        assert_eq!(upcoming[0].start_tzid, Some("America/New_York".to_string()));
+    }
+
+    #[test]
+    fn test_recurring_event_keeps_local_time_across_dst() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VTIMEZONE
+            TZID:Berlin Custom
+            BEGIN:STANDARD
+            DTSTART:19701025T030000
+            TZOFFSETFROM:+0200
+            TZOFFSETTO:+0100
+            RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10
+            END:STANDARD
+            BEGIN:DAYLIGHT
+            DTSTART:19700329T020000
+            TZOFFSETFROM:+0100
+            TZOFFSETTO:+0200
+            RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3
+            END:DAYLIGHT
+            END:VTIMEZONE
+            BEGIN:VEVENT
+            UID:weekly@example.com
+            DTSTART;TZID=Berlin Custom:20251020T100000
+            DTEND;TZID=Berlin Custom:20251020T110000
+            RRULE:FREQ=WEEKLY;COUNT=2
+            EXDATE;TZID=Berlin Custom:20251103T100000
+            SUMMARY:Weekly
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let upcoming = calendar.get_upcoming_events(utc("20251001T000000Z"));
+        let starts: Vec<_> = upcoming.iter().filter_map(|e| ical(e.start_time)).collect();
+        // 10:00 CEST before the switch, 10:00 CET after it
+        assert_eq!(starts, vec!["20251020T080000Z", "20251027T090000Z"]);
+        assert_eq!(
+            ical(upcoming[1].end_time),
+            Some("20251027T100000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mixed_time_kinds_are_compared_as_instants() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:all-day@example.com
+            DTSTART;VALUE=DATE:20251205
+            DTEND;VALUE=DATE:20251206
+            SUMMARY:Offsite
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:floating@example.com
+            DTSTART:20251204T090000
+            SUMMARY:Floating
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:utc@example.com
+            DTSTART:20251204T080000Z
+            SUMMARY:Instant
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let upcoming = calendar.get_upcoming_events(utc("20251203T120000Z"));
+        let summaries: Vec<_> = upcoming.iter().filter_map(|e| e.summary.clone()).collect();
+        assert_eq!(summaries, vec!["Instant", "Floating", "Offsite"]);
+        assert_eq!(
+            upcoming[2].start_time,
+            Some(EventTime::AllDay(
+                NaiveDate::from_ymd_opt(2025, 12, 5).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_floating_times_use_calendar_default_timezone() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            X-WR-TIMEZONE:America/New_York
+            BEGIN:VEVENT
+            UID:nyc@example.com
+            DTSTART:20251203T080000
+            SUMMARY:Standup
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        assert_eq!(
+            ical(calendar.events[0].start_time),
+            Some("20251203T130000Z".to_string())
+        );
+        assert_eq!(
+            calendar.events[0].start_tzid,
+            Some("America/New_York".to_string())
+        );
+    }
+
+    #[test]
+    fn test_all_day_span() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:holiday@example.com
+            DTSTART;VALUE=DATE:20251224
+            SUMMARY:Holiday
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:conference@example.com
+            DTSTART;VALUE=DATE:20251224
+            DTEND;VALUE=DATE:20251227
+            SUMMARY:Conference
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:meeting@example.com
+            DTSTART:20251224T100000Z
+            SUMMARY:Meeting
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 12, d).unwrap();
+        assert!(calendar.events[0].is_all_day());
+        assert_eq!(calendar.events[0].all_day_span(), Some((day(24), day(24))));
+        assert_eq!(calendar.events[1].all_day_span(), Some((day(24), day(26))));
+        assert!(!calendar.events[2].is_all_day());
+        assert_eq!(calendar.events[2].all_day_span(), None);
+    }
+
+    #[test]
+    fn test_duration_instead_of_dtend() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:ticket@example.com
+            DTSTART:20251203T100000Z
+            DURATION:PT1H30M
+            RRULE:FREQ=DAILY;COUNT=2
+            SUMMARY:Triage
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:offsite@example.com
+            DTSTART;VALUE=DATE:20251224
+            DURATION:P3D
+            SUMMARY:Offsite
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        assert_eq!(
+            ical(calendar.events[0].end_time),
+            Some("20251203T113000Z".to_string())
+        );
+        assert_eq!(
+            ical(calendar.events[1].end_time),
+            Some("20251227".to_string())
+        );
+
+        let upcoming = calendar.get_upcoming_events(utc("20251203T120000Z"));
+        assert_eq!(
+            ical(upcoming[0].end_time),
+            Some("20251204T113000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_current_events() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:workshop@example.com
+            DTSTART:20251203T100000Z
+            DTEND:20251203T120000Z
+            SUMMARY:Workshop
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T103000Z
+            DURATION:PT15M
+            RRULE:FREQ=DAILY
+            SUMMARY:Standup
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:finished@example.com
+            DTSTART:20251203T080000Z
+            DTEND:20251203T090000Z
+            SUMMARY:Finished
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:later@example.com
+            DTSTART:20251203T140000Z
+            DTEND:20251203T150000Z
+            SUMMARY:Later
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let now = utc("20251203T104000Z");
+
+        let current = calendar.get_current_events(now);
+        let summaries: Vec<_> = current.iter().filter_map(|e| e.summary.clone()).collect();
+        assert_eq!(summaries, vec!["Workshop", "Standup"]);
+        assert_eq!(
+            ical(current[1].start_time),
+            Some("20251203T103000Z".to_string())
+        );
+
+        // In-progress events are not repeated as upcoming
+        let upcoming = calendar.get_upcoming_events_limited(now, Some(1));
+        assert_eq!(upcoming[0].summary, Some("Later".to_string()));
+    }
+
+    #[test]
+    fn test_get_events_between() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:conference@example.com
+            DTSTART:20251201T090000Z
+            DTEND:20251204T170000Z
+            SUMMARY:Conference
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T103000Z
+            DURATION:PT15M
+            RRULE:FREQ=DAILY
+            SUMMARY:Standup
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:holiday@example.com
+            DTSTART;VALUE=DATE:20251203
+            SUMMARY:Holiday
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:next-day@example.com
+            DTSTART:20251204T000000Z
+            SUMMARY:Next day
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let day = calendar.get_events_between(utc("20251203T000000Z"), utc("20251204T000000Z"));
+        let summaries: Vec<_> = day.iter().filter_map(|e| e.summary.clone()).collect();
+        assert_eq!(summaries, vec!["Conference", "Holiday", "Standup"]);
+        assert_eq!(
+            ical(day[2].start_time),
+            Some("20251203T103000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:retro@example.com
+            DTSTART:20251205T140000Z
+            RRULE:FREQ=WEEKLY;COUNT=4
+            SUMMARY:Release Retro
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:planning@example.com
+            DTSTART:20251203T100000Z
+            SUMMARY:Sprint planning
+            DESCRIPTION:Agree on the scope of the next release
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:launch@example.com
+            DTSTART:20251210T100000Z
+            SUMMARY:Launch party
+            LOCATION:Release hall
+            CATEGORIES:Social
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:kickoff@example.com
+            DTSTART:20251101T100000Z
+            SUMMARY:Release kickoff
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let now = utc("20251201T120000Z");
+        let found = |query, include_past| -> Vec<(String, String)> {
+            calendar
+                .search(query, now, include_past)
+                .into_iter()
+                .map(|r| {
+                    let summary = r.event.summary.clone().unwrap();
+                    (summary, ical(r.event.start_time).unwrap())
+                })
+                .collect()
+        };
+
+        // Summary matches rank above location and description ones, and
+        // occurrences nearest to now come first
+        let release = found("RELEASE", false);
+        assert_eq!(release.len(), 6);
+        assert_eq!(
+            release[0],
+            ("Release Retro".to_string(), "20251205T140000Z".to_string())
+        );
+        assert_eq!(release[4].0, "Launch party");
+        assert_eq!(release[5].0, "Sprint planning");
+
+        // Every word must match, in any field
+        assert_eq!(found("social release", false).len(), 1);
+        assert!(found("release dinner", false).is_empty());
+        assert!(found("  ", false).is_empty());
+
+        // Past events are only searched on request, after upcoming ones
+        assert!(found("kickoff", false).is_empty());
+        let with_past = found("release kickoff", true);
+        assert_eq!(
+            with_past,
+            vec![(
+                "Release kickoff".to_string(),
+                "20251101T100000Z".to_string()
+            )]
+        );
+        assert_eq!(found("retro", true).len(), 4);
+    }
+
+    #[test]
+    fn test_parse_status_and_transparency() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:weekly@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=WEEKLY;COUNT=3
+            STATUS:CONFIRMED
+            TRANSP:OPAQUE
+            SUMMARY:Weekly
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:weekly@example.com
+            RECURRENCE-ID:20251208T100000Z
+            DTSTART:20251208T100000Z
+            STATUS:CANCELLED
+            SUMMARY:Weekly
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:maybe@example.com
+            DTSTART:20251209T100000Z
+            STATUS:TENTATIVE
+            TRANSP:TRANSPARENT
+            SUMMARY:Maybe
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        assert_eq!(calendar.events[0].status, Some(EventStatus::Confirmed));
+        assert_eq!(calendar.events[0].transparency, Some(Transparency::Opaque));
+        assert!(calendar.events[2].is_tentative());
+        assert!(calendar.events[2].is_transparent());
+
+        let upcoming = calendar.get_upcoming_events(utc("20251202T000000Z"));
+        let cancelled: Vec<_> = upcoming.iter().map(|e| e.is_cancelled()).collect();
+        assert_eq!(cancelled, vec![true, false, false]);
+    }
+
+    #[test]
+    fn test_parse_organizer_and_attendees() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:planning@example.com
+            DTSTART:20251201T100000Z
+            SUMMARY:Planning
+            ORGANIZER;CN=Alice:mailto:alice@example.com
+            ATTENDEE;CN=Bob;PARTSTAT=ACCEPTED;ROLE=CHAIR:mailto:bob@example.com
+            ATTENDEE;PARTSTAT=TENTATIVE;ROLE=OPT-PARTICIPANT:mailto:carol@example.com
+            ATTENDEE;PARTSTAT=ACCEPTED:mailto:dave@example.com
+            ATTENDEE:mailto:erin@example.com
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let event = &calendar.events[0];
+
+        let organizer = event.organizer.as_ref().unwrap();
+        assert_eq!(organizer.display_name(), "Alice");
+        assert_eq!(organizer.address, "mailto:alice@example.com");
+
+        assert_eq!(event.attendees.len(), 4);
+        assert_eq!(event.attendees[0].role, ParticipantRole::Chair);
+        assert_eq!(event.attendees[1].role, ParticipantRole::Optional);
+        assert_eq!(event.attendees[1].display_name(), "carol@example.com");
+        assert_eq!(event.attendees[3].status, ParticipationStatus::NeedsAction);
+        assert_eq!(event.attendee_count(ParticipationStatus::Accepted), 2);
+        assert_eq!(event.attendee_count(ParticipationStatus::Tentative), 1);
+    }
+
+    #[test]
+    fn test_parse_descriptive_and_custom_properties() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:release@example.com
+            DTSTART:20251201T100000Z
+            SUMMARY:Release
+            CATEGORIES:release,oncall
+            CATEGORIES:Social
+            PRIORITY:2
+            CLASS:CONFIDENTIAL
+            GEO:52.52;13.405
+            ATTACH:https://example.com/agenda.pdf
+            X-TEAM:platform
+            X-TRACKER-ID:REL-42
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:plain@example.com
+            DTSTART:20251202T100000Z
+            SUMMARY:Plain
+            PRIORITY:0
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let event = &calendar.events[0];
+
+        assert_eq!(event.categories, vec!["release", "oncall", "Social"]);
+        assert!(event.has_category("social"));
+        assert_eq!(event.priority, Some(2));
+        assert_eq!(event.classification, Some(Classification::Confidential));
+        assert!(event.is_private());
+        assert_eq!(event.geo, Some((52.52, 13.405)));
+        assert_eq!(event.attachments, vec!["https://example.com/agenda.pdf"]);
+        assert_eq!(event.custom_properties["X-TEAM"], "platform");
+        assert_eq!(event.custom_properties["X-TRACKER-ID"], "REL-42");
+
+        let plain = &calendar.events[1];
+        assert_eq!(plain.priority, None);
+        assert!(!plain.is_private());
+        assert!(plain.custom_properties.is_empty());
+    }
+
+    #[test]
+    fn test_parse_tasks() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            X-WR-TIMEZONE:Europe/Berlin
+            BEGIN:VTODO
+            UID:notes@example.com
+            SUMMARY:Write release notes
+            DUE:20251205T170000
+            PRIORITY:1
+            STATUS:IN-PROCESS
+            PERCENT-COMPLETE:40
+            CATEGORIES:release
+            END:VTODO
+            BEGIN:VTODO
+            UID:tag@example.com
+            SUMMARY:Tag release
+            DUE;VALUE=DATE:20251203
+            END:VTODO
+            BEGIN:VTODO
+            UID:done@example.com
+            SUMMARY:Freeze branch
+            DUE:20251201T120000Z
+            STATUS:COMPLETED
+            END:VTODO
+            BEGIN:VTODO
+            UID:someday@example.com
+            SUMMARY:Clean up wiki
+            END:VTODO
+            BEGIN:VTODO
+            UID:later@example.com
+            SUMMARY:Announce release
+            DUE:20251220T120000Z
+            END:VTODO
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        assert_eq!(calendar.tasks.len(), 5);
+        assert!(calendar.events.is_empty());
+
+        let notes = &calendar.tasks[0];
+        assert_eq!(ical(notes.due), Some("20251205T160000Z".to_string()));
+        assert_eq!(notes.priority, Some(1));
+        assert_eq!(notes.status, Some(TaskStatus::InProcess));
+        assert_eq!(notes.percent_complete, Some(40));
+        assert_eq!(notes.categories, vec!["release"]);
+
+        let summaries = |tasks: Vec<CalendarTask>| -> Vec<String> {
+            tasks.into_iter().filter_map(|t| t.summary).collect()
+        };
+        assert_eq!(
+            summaries(calendar.get_open_tasks()),
+            vec![
+                "Tag release",
+                "Write release notes",
+                "Announce release",
+                "Clean up wiki"
+            ]
+        );
+
+        let now = utc("20251204T000000Z");
+        assert_eq!(
+            summaries(calendar.get_tasks_due_before(now + Duration::days(7))),
+            vec!["Tag release", "Write release notes"]
+        );
+        assert!(calendar.tasks[1].is_overdue(now));
+        assert!(!calendar.tasks[2].is_overdue(now));
+    }
+
+    #[test]
+    fn test_alarms_between() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T100000Z
+            DTEND:20251201T101500Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER:-PT15M
+            END:VALARM
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER;VALUE=DATE-TIME:20251201T080000Z
+            END:VALARM
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:quiet@example.com
+            DTSTART:20251201T120000Z
+            SUMMARY:No alarms
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        assert_eq!(calendar.events[0].alarms.len(), 2);
+
+        let alarms = calendar.alarms_between(utc("20251201T000000Z"), utc("20251202T120000Z"));
+        let times: Vec<String> = alarms.iter().map(|(t, _)| t.to_rfc3339()).collect();
+        assert_eq!(
+            times,
+            vec![
+                "2025-12-01T08:00:00+00:00",
+                "2025-12-01T09:45:00+00:00",
+                "2025-12-02T09:45:00+00:00",
+            ]
+        );
+        assert_eq!(
+            ical(alarms[2].1.start_time),
+            Some("20251202T100000Z".to_string())
+        );
+
+        // The window start is exclusive so an alarm is only reported once
+        let alarms = calendar.alarms_between(utc("20251201T094500Z"), utc("20251201T100000Z"));
+        assert!(alarms.is_empty());
+    }
+
+    #[test]
+    fn test_absolute_alarm_on_old_series() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20200101T100000Z
+            RRULE:FREQ=DAILY
+            SUMMARY:Standup
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER;VALUE=DATE-TIME:20251201T080000Z
+            END:VALARM
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        // Absolute triggers do not widen the search around each occurrence
+        assert_eq!(calendar.alarm_reach(), (Duration::zero(), Duration::zero()));
+
+        let alarms = calendar.alarms_between(utc("20251201T000000Z"), utc("20251202T000000Z"));
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].0, utc("20251201T080000Z"));
+        assert_eq!(
+            ical(alarms[0].1.start_time),
+            Some("20251201T100000Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_alarms_far_from_the_event() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:offsite@example.com
+            DTSTART:20260115T090000Z
+            DTEND:20260116T170000Z
+            SUMMARY:Offsite
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER:-P14D
+            END:VALARM
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER;RELATED=END:P10D
+            END:VALARM
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let alarms = calendar.alarms_between(utc("20260101T000000Z"), utc("20260102T000000Z"));
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].0, utc("20260101T090000Z"));
+
+        let alarms = calendar.alarms_between(utc("20260126T000000Z"), utc("20260127T000000Z"));
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].0, utc("20260126T170000Z"));
+    }
+
+    #[test]
+    fn test_starts_between() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            UID:standup@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            SUMMARY:Standup
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:holiday@example.com
+            DTSTART;VALUE=DATE:20251202
+            SUMMARY:Holiday
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let starting = calendar.starts_between(utc("20251201T100000Z"), utc("20251203T100000Z"));
+        let starts: Vec<_> = starting.iter().map(|e| ical(e.start_time)).collect();
+        assert_eq!(
+            starts,
+            vec![
+                Some("20251202T100000Z".to_string()),
+                Some("20251203T100000Z".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_link() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Test//Test//EN
+            BEGIN:VEVENT
+            DTSTART:20251201T100000Z
+            SUMMARY:With URL
+            URL:https://meet.example.com/abc
+            LOCATION:https://other.example.com/room
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART:20251201T100000Z
+            SUMMARY:Link in description
+            LOCATION:Room 4
+            DESCRIPTION:Join at https://zoom.example.com/j/123. Bring notes
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART:20251201T100000Z
+            SUMMARY:No link
+            LOCATION:Room 4
+            END:VEVENT
+            END:VCALENDAR
+        "};
+
+        let calendar = IcalCalendar::parse_ical_content(ical_content).unwrap();
+        let links: Vec<_> = calendar.events.iter().map(|e| e.join_link()).collect();
+        assert_eq!(
+            links,
+            vec![
+                Some("https://meet.example.com/abc"),
+                Some("https://zoom.example.com/j/123"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_tags_sources() {
+        let team = IcalCalendar::parse_ical_content(indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:sync@example.com
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=2
+            SUMMARY:Sync
+            END:VEVENT
+            BEGIN:VTODO
+            SUMMARY:Book room
+            END:VTODO
+            END:VCALENDAR
+        "})
+        .unwrap();
+        // Same UID in another calendar must not override the team occurrence
+        let conferences = IcalCalendar::parse_ical_content(indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:sync@example.com
+            RECURRENCE-ID:20251202T100000Z
+            DTSTART:20251202T150000Z
+            SUMMARY:Keynote
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let source = |name: &str| EventSource {
+            name: name.to_string(),
+            color: None,
+        };
+        let (team_source, conference_source) = (source("team"), source("conferences"));
+
+        let merged =
+            IcalCalendar::merge([(&team_source, &team), (&conference_source, &conferences)]);
+        assert_eq!(merged.tasks[0].source, Some(team_source.clone()));
+
+        let listed: Vec<_> = merged
+            .get_upcoming_events(utc("20251130T000000Z"))
+            .iter()
+            .map(|e| {
+                (
+                    e.summary.clone().unwrap(),
+                    e.source.as_ref().unwrap().name.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("Sync".to_string(), "team".to_string()),
+                ("Sync".to_string(), "team".to_string()),
+                ("Keynote".to_string(), "conferences".to_string()),
+            ]
+        );
+    }
 }
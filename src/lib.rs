@@ -1,2 +1,11 @@
+pub mod alarm;
+pub mod cache;
+pub mod calendars;
+pub mod changes;
+pub mod commands;
 pub mod config;
 pub mod ical;
+pub mod paging;
+pub mod recurrence;
+pub mod time;
+pub mod timezone;
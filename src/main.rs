@@ -1,12 +1,29 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use clap::Parser;
 use daemonize::Daemonize;
-use matrix_bot_ical::config::{self, Config, ReminderType, should_ignore_user};
-use matrix_bot_ical::ical::IcalCalendar;
+use matrix_bot_ical::cache::{CalendarCache, CalendarSnapshot};
+use matrix_bot_ical::calendars::CalendarSet;
+use matrix_bot_ical::changes::{CalendarChange, SeriesChange, diff_calendars};
+use matrix_bot_ical::commands::{
+    CommandError, CommandRegistry, ParseContext, ParsedCommand, bot_commands,
+};
+use matrix_bot_ical::config::{
+    Config, DisplayConfig, ReminderConfig, ReminderType, calendars_for_reminder,
+    calendars_for_room, event_in_calendars, should_ignore_user, should_show_event,
+};
+use matrix_bot_ical::ical::{
+    CalendarEvent, CalendarTask, EventSource, IcalCalendar, ParticipationStatus, SearchResult,
+};
+use matrix_bot_ical::paging::{PageCursor, PageCursors};
+use matrix_bot_ical::time::EventTime;
+use matrix_bot_ical::timezone::parse_iana_name;
 use matrix_sdk::{
     Client, Room, RoomState, SessionMeta, SessionTokens,
     authentication::matrix::MatrixSession,
     config::SyncSettings,
+    ruma::events::Mentions,
     ruma::events::room::member::{MembershipState, StrippedRoomMemberEvent},
     ruma::events::room::message::{
         MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent,
@@ -14,8 +31,19 @@ use matrix_sdk::{
     ruma::{RoomId, UserId, device_id},
 };
 use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
+/// How many days ahead `!due` and the DueTasks reminder look for tasks.
+const DUE_SOON_DAYS: i64 = 7;
+
+/// How many days ahead changes to events are announced.
+const CHANGE_WINDOW_DAYS: i64 = 90;
+
+/// How often the event scheduler fetches the calendar and plans messages.
+const EVENT_SCHEDULER_INTERVAL: Duration = Duration::minutes(1);
+
 #[derive(Parser)]
 #[command(name = "matrix-bot-ical")]
 #[command(about = "A Matrix bot for iCal / WebCal")]
@@ -47,8 +75,13 @@ fn main() -> Result<()> {
     // Validate reminder configurations before starting bot
     validate_reminders(&config)?;
 
-    if (IcalCalendar::from_url_blocking(&config.webcal)).is_ok() {
-        println!("Calendar fetched and parsed: {}", &config.webcal);
+    for calendar in &config.calendars {
+        if (IcalCalendar::from_url_blocking(&calendar.url)).is_ok() {
+            println!(
+                "Calendar '{}' fetched and parsed: {}",
+                calendar.name, calendar.url
+            );
+        }
     }
 
     // Daemonize if requested
@@ -123,12 +156,27 @@ async fn run_bot(config: &Config) -> Result<()> {
         .expect("Client should have a user ID")
         .to_owned();
 
+    // Shared calendar caches, refreshed in the background
+    let calendars = Arc::new(calendar_set(config));
+    calendars.spawn_refreshers();
+
     // Add event handler for room messages
-    let bot_filtering = config.bot_filtering.clone();
     let config_clone = config.clone();
+    let calendars_clone = Arc::clone(&calendars);
+    let commands = Arc::new(bot_commands());
+    let cursors = Arc::new(PageCursors::new());
     client.add_event_handler(
         move |event: OriginalSyncRoomMessageEvent, room: Room| async move {
-            on_room_message(event, room, &bot_user_id, &bot_filtering, &config_clone).await
+            on_room_message(
+                event,
+                room,
+                &bot_user_id,
+                &config_clone,
+                &calendars_clone,
+                &commands,
+                &cursors,
+            )
+            .await
         },
     );
 
@@ -136,7 +184,27 @@ async fn run_bot(config: &Config) -> Result<()> {
     client.add_event_handler(on_stripped_state_member);
 
     // Setup cron scheduler for reminders
-    setup_reminder_scheduler(&client, config).await?;
+    setup_reminder_scheduler(&client, config, &calendars).await?;
+
+    // Send alarms and event-relative reminders at their times
+    if config.alarms.matrix_room.is_some() || has_event_reminders(config) {
+        tokio::spawn(run_event_scheduler(
+            client.clone(),
+            config.clone(),
+            Arc::clone(&calendars),
+        ));
+        println!("Event scheduler started");
+    }
+
+    // Announce calendar changes to subscribed rooms
+    if !config.change_notifications.matrix_rooms.is_empty() {
+        tokio::spawn(run_change_notifier(
+            client.clone(),
+            config.clone(),
+            Arc::clone(&calendars),
+        ));
+        println!("Change notifications started");
+    }
 
     // Start continuous sync
     let settings = SyncSettings::default().token(response.next_batch);
@@ -146,12 +214,33 @@ async fn run_bot(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Build the calendar set from the configured feeds. The last good copy of
+/// each feed is kept on disk as a fallback when its server is down.
+fn calendar_set(config: &Config) -> CalendarSet {
+    config
+        .calendars
+        .iter()
+        .fold(CalendarSet::new(), |calendars, calendar| {
+            let cache_file = Path::new(&config.working_dir)
+                .join(format!("calendar-cache-{}.ics", calendar.name));
+            calendars.with_feed(
+                EventSource {
+                    name: calendar.name.clone(),
+                    color: calendar.color.clone(),
+                },
+                CalendarCache::new(&calendar.url, config.refresh_interval).persist_to(cache_file),
+            )
+        })
+}
+
 async fn on_room_message(
     event: OriginalSyncRoomMessageEvent,
     room: Room,
     bot_user_id: &UserId,
-    bot_filtering: &config::BotFilteringConfig,
     config: &Config,
+    calendars: &CalendarSet,
+    commands: &CommandRegistry,
+    cursors: &PageCursors,
 ) {
     // Only respond to messages in joined rooms
     if room.state() != RoomState::Joined {
@@ -163,35 +252,124 @@ async fn on_room_message(
     };
 
     // Check if sender should be ignored based on bot filtering configuration
-    if should_ignore_user(event.sender.as_str(), bot_user_id.as_str(), bot_filtering) {
+    if should_ignore_user(
+        event.sender.as_str(),
+        bot_user_id.as_str(),
+        &config.bot_filtering,
+    ) {
         println!("Ignoring message from filtered user: {}", event.sender);
         return;
     }
 
-    // Check if message is for meetings/events
-    if text_content.body.starts_with("!meetings") || text_content.body.starts_with("!events") {
-        println!(
-            "Received meetings/events request in room {}",
-            room.room_id()
-        );
+    let names = calendars.names();
+    let context = ParseContext {
+        today: local_date(Utc::now(), &config.display),
+        calendars: &names,
+    };
+    let command = match commands.parse(&text_content.body, &context) {
+        Some(Ok(command)) => command,
+        Some(Err(e)) if !matches!(e, CommandError::UnknownCommand(_)) => {
+            let reply = RoomMessageEventContent::text_plain(e.to_string());
+            if let Err(e) = room.send(reply).await {
+                eprintln!("Failed to send command error: {}", e);
+            }
+            return;
+        }
+        // Not a command, or one meant for another bot
+        _ => return,
+    };
+
+    println!(
+        "Received !{} request in room {}",
+        command.name,
+        room.room_id()
+    );
 
-        let response =
-            RoomMessageEventContent::text_markdown(handle_meetings_events_request(config).await);
+    let response = RoomMessageEventContent::text_markdown(
+        handle_command(
+            &command,
+            room.room_id(),
+            &event.sender,
+            config,
+            calendars,
+            commands,
+            cursors,
+        )
+        .await,
+    );
 
-        if let Err(e) = room.send(response).await {
-            eprintln!("Failed to send meetings/events message: {}", e);
-        }
+    if let Err(e) = room.send(response).await {
+        eprintln!("Failed to send !{} message: {}", command.name, e);
     }
-    // Check if message is for meeting/event
-    else if text_content.body.starts_with("!meeting") || text_content.body.starts_with("!event") {
-        println!("Received meeting/event request in room {}", room.room_id());
+}
 
-        let response =
-            RoomMessageEventContent::text_markdown(handle_meeting_event_request(config).await);
+/// Answer a parsed command sent in a room.
+async fn handle_command(
+    command: &ParsedCommand,
+    room_id: &RoomId,
+    sender: &UserId,
+    config: &Config,
+    calendars: &CalendarSet,
+    commands: &CommandRegistry,
+    cursors: &PageCursors,
+) -> String {
+    // Calendars named in the command, otherwise those bound to the room
+    let selection = match command.calendars() {
+        [] => calendars_for_room(room_id.as_str(), &config.room_calendars),
+        named => named,
+    };
 
-        if let Err(e) = room.send(response).await {
-            eprintln!("Failed to send meeting/event message: {}", e);
+    match command.name {
+        "meeting" => handle_meeting_event_request(config, calendars, selection).await,
+        "meetings" => {
+            let page = PageCursor {
+                selection: selection.to_vec(),
+                since: Utc::now(),
+                offset: 0,
+                page_size: command.count("count").unwrap_or(config.display.max_events),
+            };
+            handle_meetings_page(config, calendars, cursors, room_id, sender, page).await
+        }
+        "more" => match cursors.take(room_id.as_str(), sender.as_str(), Utc::now()) {
+            Some(page) => {
+                handle_meetings_page(config, calendars, cursors, room_id, sender, page).await
+            }
+            None => "Nothing more to show. Send `!meetings` to list upcoming events.".to_string(),
+        },
+        "today" | "tomorrow" | "week" | "month" | "agenda" => {
+            let today = local_date(Utc::now(), &config.display);
+            let (first, last, period) = match command.name {
+                "today" => (today, today, "today".to_string()),
+                "tomorrow" => {
+                    let tomorrow = today + Duration::days(1);
+                    (tomorrow, tomorrow, "tomorrow".to_string())
+                }
+                "week" => (
+                    today,
+                    today + Duration::days(6),
+                    "in the next 7 days".to_string(),
+                ),
+                "month" => (
+                    today,
+                    today + Months::new(1) - Duration::days(1),
+                    "in the next month".to_string(),
+                ),
+                _ => {
+                    let (first, last) = command.date_range("date").unwrap_or((today, today));
+                    (first, last, format_period(first, last))
+                }
+            };
+            handle_agenda_request(config, calendars, selection, first, last, &period).await
         }
+        "tasks" => handle_tasks_request(config, calendars, selection).await,
+        "due" => handle_due_tasks_request(config, calendars, selection).await,
+        "search" | "search-all" => {
+            let query = command.text("text").unwrap_or_default();
+            let include_past = command.name == "search-all";
+            handle_search_request(config, calendars, selection, query, include_past).await
+        }
+        "help" => commands.help_for(command.text("command")),
+        name => format!("Command !{} is not available", name),
     }
 }
 
@@ -231,130 +409,973 @@ async fn on_stripped_state_member(event: StrippedRoomMemberEvent, client: Client
     }
 }
 
-fn format_ical_date(ical_date: &str) -> String {
-    match chrono::DateTime::parse_from_str(ical_date, "%Y%m%dT%H%M%SZ") {
-        Ok(dt) => dt.format("%a, %b %d, %Y at %I:%M %p").to_string(),
-        Err(_) => {
-            // Try parsing without timezone
-            match chrono::NaiveDateTime::parse_from_str(ical_date, "%Y%m%dT%H%M%S") {
-                Ok(dt) => dt.format("%a, %b %d, %Y at %I:%M %p").to_string(),
-                Err(_) => ical_date.to_string(), // Return original if parsing fails
-            }
+/// Zone an event's times are shown in: the configured one, otherwise the
+/// event's own TZID if it names an IANA zone. `None` means UTC.
+fn display_zone(tzid: Option<&str>, display: &DisplayConfig) -> Option<Tz> {
+    display.timezone.or_else(|| tzid.and_then(parse_iana_name))
+}
+
+/// The day a time falls on in the configured zone (UTC if none).
+fn local_date(time: DateTime<Utc>, display: &DisplayConfig) -> NaiveDate {
+    match display.timezone {
+        Some(zone) => time.with_timezone(&zone).date_naive(),
+        None => time.date_naive(),
+    }
+}
+
+/// When a day starts in the configured zone (UTC if none).
+fn day_start(day: NaiveDate, display: &DisplayConfig) -> DateTime<Utc> {
+    let midnight = day.and_time(NaiveTime::MIN);
+    match display.timezone {
+        // Where midnight is skipped by a DST change, the day starts an hour later
+        Some(zone) => zone
+            .from_local_datetime(&midnight)
+            .earliest()
+            .or_else(|| {
+                zone.from_local_datetime(&(midnight + Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or_else(|| midnight.and_utc(), |start| start.to_utc()),
+        None => midnight.and_utc(),
+    }
+}
+
+/// Render a time, converting instants to the given zone (UTC if none) and
+/// labelling it, e.g. "Wed, Dec 03, 2025 at 08:00 AM EST".
+fn format_event_time(time: &EventTime, zone: Option<Tz>) -> String {
+    match time {
+        EventTime::Instant(dt) => match zone {
+            Some(zone) => dt
+                .with_timezone(&zone)
+                .format("%a, %b %d, %Y at %I:%M %p %Z")
+                .to_string(),
+            None => dt.format("%a, %b %d, %Y at %I:%M %p UTC").to_string(),
+        },
+        EventTime::Floating(dt) => dt.format("%a, %b %d, %Y at %I:%M %p").to_string(),
+        EventTime::AllDay(date) => date.format("%a, %b %d, %Y").to_string(),
+    }
+}
+
+/// Render an event as a markdown block. Events without a summary are skipped.
+fn format_event(event: &CalendarEvent, display: &DisplayConfig) -> String {
+    let Some(summary) = &event.summary else {
+        return String::new();
+    };
+
+    let hide_details = hides_details(event, display);
+
+    let title = match &event.url {
+        _ if hide_details => "**Private event**".to_string(),
+        Some(url) => format!("**[{}]({})**", summary, url),
+        None => format!("**{}**", summary),
+    };
+
+    let mut response = if event.is_cancelled() {
+        format!("~~{}~~ (cancelled)", title)
+    } else if event.is_tentative() {
+        format!("{} (tentative)", title)
+    } else {
+        title
+    };
+    if let Some(source) = &event.source {
+        response.push_str(&format!(" · {}", format_source(source)));
+    }
+    response.push('\n');
+
+    if event.is_transparent() {
+        response.push_str("* Shows as: free\n");
+    }
+
+    if let Some((first_day, last_day)) = event.all_day_span() {
+        response.push_str(&format!("* {}\n", format_all_day_span(first_day, last_day)));
+    } else {
+        let zone = display_zone(event.start_tzid.as_deref(), display);
+        if let Some(start_time) = &event.start_time {
+            response.push_str(&format!(
+                "* Starts: {}\n",
+                format_event_time(start_time, zone)
+            ));
+        }
+
+        if let Some(end_time) = &event.end_time {
+            response.push_str(&format!("* Ends: {}\n", format_event_time(end_time, zone)));
+        }
+    }
+
+    if hide_details {
+        response.push_str("\n\n");
+        return response;
+    }
+
+    if let Some(location) = &event.location {
+        response.push_str(&format!("* Location: {}\n", location));
+    }
+
+    if display.show_attendance
+        && let Some(attendance) = format_attendance(event)
+    {
+        response.push_str(&format!("* {}\n", attendance));
+    }
+
+    response.push_str("\n\n");
+    response
+}
+
+/// Whether only the time of an event may be shown: private and confidential
+/// events keep their details unless configured otherwise.
+fn hides_details(event: &CalendarEvent, display: &DisplayConfig) -> bool {
+    event.is_private() && !display.show_private_details
+}
+
+/// Render the calendar an event or task came from, e.g. "🟦 team".
+fn format_source(source: &EventSource) -> String {
+    let swatch = match source.color.as_deref() {
+        Some("red") => "🟥 ",
+        Some("orange") => "🟧 ",
+        Some("yellow") => "🟨 ",
+        Some("green") => "🟩 ",
+        Some("blue") => "🟦 ",
+        Some("purple") => "🟪 ",
+        Some("brown") => "🟫 ",
+        Some("black") => "⬛ ",
+        Some("white") => "⬜ ",
+        _ => "",
+    };
+    format!("{}{}", swatch, source.name)
+}
+
+/// Render who runs an event and how attendees replied, e.g.
+/// "Organizer: Alice — 5 accepted, 2 tentative".
+fn format_attendance(event: &CalendarEvent) -> Option<String> {
+    let replies: Vec<String> = [
+        (ParticipationStatus::Accepted, "accepted"),
+        (ParticipationStatus::Tentative, "tentative"),
+        (ParticipationStatus::Declined, "declined"),
+    ]
+    .into_iter()
+    .map(|(status, label)| (event.attendee_count(status), label))
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{} {}", count, label))
+    .collect();
+
+    match (&event.organizer, replies.is_empty()) {
+        (Some(organizer), true) => Some(format!("Organizer: {}", organizer.display_name())),
+        (Some(organizer), false) => Some(format!(
+            "Organizer: {} — {}",
+            organizer.display_name(),
+            replies.join(", ")
+        )),
+        (None, false) => Some(format!("Attendees: {}", replies.join(", "))),
+        (None, true) => None,
+    }
+}
+
+/// Render the days covered by an all-day event, e.g. "All day, Wed Dec 24"
+/// or "Dec 24 – Dec 26".
+fn format_all_day_span(first_day: NaiveDate, last_day: NaiveDate) -> String {
+    if first_day == last_day {
+        format!("All day, {}", first_day.format("%a %b %-d"))
+    } else if first_day.year() == last_day.year() {
+        format!(
+            "{} – {}",
+            first_day.format("%b %-d"),
+            last_day.format("%b %-d")
+        )
+    } else {
+        format!(
+            "{} – {}",
+            first_day.format("%b %-d, %Y"),
+            last_day.format("%b %-d, %Y")
+        )
+    }
+}
+
+/// Render the "happening now" section, or nothing if no event is in progress.
+fn format_current_events(current_events: &[CalendarEvent], display: &DisplayConfig) -> String {
+    if current_events.is_empty() {
+        return String::new();
+    }
+
+    let mut response = String::from("# Happening Now\n\n");
+    for event in current_events {
+        response.push_str(&format_event(event, display));
+    }
+    response
+}
+
+/// Note when a response is served from a copy of the calendar that could
+/// not be refreshed, e.g. "(cached as of 10:32 UTC)", in the configured zone.
+fn cache_note(
+    snapshot: &CalendarSnapshot,
+    now: DateTime<Utc>,
+    display: &DisplayConfig,
+) -> Option<String> {
+    if !snapshot.outdated {
+        return None;
+    }
+    let format = if local_date(snapshot.fetched_at, display) == local_date(now, display) {
+        "%H:%M"
+    } else {
+        "%b %-d, %H:%M"
+    };
+    let fetched_at = match display.timezone {
+        Some(zone) => snapshot
+            .fetched_at
+            .with_timezone(&zone)
+            .format(&format!("{} %Z", format))
+            .to_string(),
+        None => snapshot
+            .fetched_at
+            .format(&format!("{} UTC", format))
+            .to_string(),
+    };
+    Some(format!("(cached as of {})", fetched_at))
+}
+
+fn with_cache_note(
+    mut response: String,
+    snapshot: &CalendarSnapshot,
+    display: &DisplayConfig,
+) -> String {
+    if let Some(note) = cache_note(snapshot, Utc::now(), display) {
+        if !response.ends_with('\n') {
+            response.push(' ');
         }
+        response.push_str(&format!("_{}_", note));
     }
+    response
 }
 
-async fn handle_meeting_event_request(config: &Config) -> String {
-    if config.webcal.is_empty() {
-        return "No webcal URL configured".to_string();
+async fn handle_meeting_event_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    selection: &[String],
+) -> String {
+    if calendars.is_empty() {
+        return "No calendar configured".to_string();
     }
 
-    let calendar = match IcalCalendar::from_url(&config.webcal).await {
-        Ok(calendar) => calendar,
+    let snapshot = match calendars.get(selection).await {
+        Ok(snapshot) => snapshot,
         Err(_) => return "There was a problem fetching the calendar".to_string(),
     };
+    let calendar = &snapshot.calendar;
+
+    let now = chrono::Utc::now();
+    let filter = &config.event_filtering;
+    let mut current_events = calendar.get_current_events(now);
+    current_events.retain(|event| should_show_event(event, filter));
+    let mut upcoming_events = calendar.get_upcoming_events(now);
+    upcoming_events.retain(|event| should_show_event(event, filter));
+    upcoming_events.truncate(1);
+
+    if current_events.is_empty() && upcoming_events.is_empty() {
+        return with_cache_note(
+            "No upcoming events found.".to_string(),
+            &snapshot,
+            &config.display,
+        );
+    }
 
-    let current_time = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
-    let upcoming_events = calendar.get_upcoming_events_limited(&current_time, Some(1));
+    let mut response = format_current_events(&current_events, &config.display);
 
-    if upcoming_events.is_empty() {
-        return "No upcoming events found.".to_string();
+    if let Some(event) = upcoming_events.first() {
+        response.push_str("# Next Meeting/Event\n\n");
+        response.push_str(&format_event(event, &config.display));
     }
 
-    let event = upcoming_events[0];
-    let mut response = String::new();
-    response.push_str("# Next Meeting/Event\n\n");
+    // Add info URL if configured
+    if let Some(info_url) = &config.info_url {
+        response.push_str(&format!("\nFor more information: {}\n", info_url));
+    }
+
+    with_cache_note(response, &snapshot, &config.display)
+}
+
+/// Render one page of the upcoming events listing, after the events in
+/// progress on the first page. Returns the response and how many events
+/// are left after this page.
+fn format_meetings_page(
+    current_events: &[CalendarEvent],
+    upcoming_events: &[CalendarEvent],
+    page: &PageCursor,
+    can_continue: bool,
+    display: &DisplayConfig,
+) -> (String, usize) {
+    let mut response = if page.offset == 0 {
+        format_current_events(current_events, display)
+    } else {
+        String::new()
+    };
 
-    if let Some(summary) = &event.summary {
-        if let Some(url) = &event.url {
-            response.push_str(&format!("**[{}]({})**\n", summary, url));
+    let shown: Vec<&CalendarEvent> = upcoming_events
+        .iter()
+        .skip(page.offset)
+        .take(page.page_size)
+        .collect();
+    let remaining = upcoming_events
+        .len()
+        .saturating_sub(page.offset + shown.len());
+
+    if !shown.is_empty() {
+        if page.offset == 0 {
+            response.push_str("# Upcoming Meetings/Events\n\n");
         } else {
-            response.push_str(&format!("**{}**\n", summary));
+            response.push_str("# More Meetings/Events\n\n");
         }
 
-        if let Some(start_time) = &event.start_time {
-            response.push_str(&format!("* Starts: {}\n", format_ical_date(start_time)));
+        for event in shown {
+            response.push_str(&format_event(event, display));
         }
+    }
 
-        if let Some(end_time) = &event.end_time {
-            response.push_str(&format!("* Ends: {}\n", format_ical_date(end_time)));
+    if remaining > 0 {
+        if can_continue {
+            response.push_str(&format!(
+                "_…and {} more. Send `!more` for the next {}._\n",
+                remaining,
+                remaining.min(page.page_size)
+            ));
+        } else {
+            response.push_str(&format!("_…and {} more._\n", remaining));
         }
+    }
+
+    (response, remaining)
+}
+
+/// List upcoming events a page at a time. Returns the response and how many
+/// events are left after this page; with `can_continue` the response tells
+/// the user to send `!more` for them.
+async fn handle_meetings_events_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    page: &PageCursor,
+    can_continue: bool,
+) -> (String, usize) {
+    if calendars.is_empty() {
+        return ("No calendar configured".to_string(), 0);
+    }
+
+    let snapshot = match calendars.get(&page.selection).await {
+        Ok(snapshot) => snapshot,
+        Err(_) => return ("There was a problem fetching the calendar".to_string(), 0),
+    };
+    let calendar = &snapshot.calendar;
+
+    let filter = &config.event_filtering;
+    let mut current_events = calendar.get_current_events(page.since);
+    current_events.retain(|event| should_show_event(event, filter));
+    let mut upcoming_events = calendar.get_upcoming_events(page.since);
+    upcoming_events.retain(|event| should_show_event(event, filter));
+
+    if current_events.is_empty() && upcoming_events.is_empty() {
+        let response = with_cache_note(
+            "No upcoming events found.".to_string(),
+            &snapshot,
+            &config.display,
+        );
+        return (response, 0);
+    }
+
+    let (mut response, remaining) = format_meetings_page(
+        &current_events,
+        &upcoming_events,
+        page,
+        can_continue,
+        &config.display,
+    );
 
-        if let Some(location) = &event.location {
-            response.push_str(&format!("* Location: {}\n", location));
+    // Add info URL if configured
+    if let Some(info_url) = &config.info_url {
+        response.push_str(&format!("\nFor more information: {}\n", info_url));
+    }
+
+    (
+        with_cache_note(response, &snapshot, &config.display),
+        remaining,
+    )
+}
+
+/// Answer with a page of upcoming events, remembering where it ends so the
+/// sender can continue with `!more`.
+async fn handle_meetings_page(
+    config: &Config,
+    calendars: &CalendarSet,
+    cursors: &PageCursors,
+    room_id: &RoomId,
+    sender: &UserId,
+    page: PageCursor,
+) -> String {
+    let (response, remaining) =
+        handle_meetings_events_request(config, calendars, &page, true).await;
+    if remaining > 0 {
+        let next = PageCursor {
+            offset: page.offset + page.page_size,
+            ..page
+        };
+        cursors.set(room_id.as_str(), sender.as_str(), next, Utc::now());
+    }
+    response
+}
+
+/// Describe a range of days, e.g. "on Wed, Dec 03, 2025".
+fn format_period(first: NaiveDate, last: NaiveDate) -> String {
+    if first == last {
+        format!("on {}", first.format("%a, %b %d, %Y"))
+    } else {
+        format!(
+            "from {} to {}",
+            first.format("%a, %b %d, %Y"),
+            last.format("%a, %b %d, %Y")
+        )
+    }
+}
+
+/// Render the events overlapping a range of days, grouped by the day they
+/// start (or the first day, for events already running).
+fn format_agenda(
+    events: &[CalendarEvent],
+    first: NaiveDate,
+    last: NaiveDate,
+    period: &str,
+    display: &DisplayConfig,
+) -> String {
+    let mut response = format!("# Events {}\n\n", period);
+    let mut current_day = None;
+
+    for event in events {
+        let day = event.start_time.map_or(first, |start| {
+            let day = match start {
+                EventTime::Instant(dt) => local_date(dt, display),
+                other => other.naive().date(),
+            };
+            day.max(first)
+        });
+        if first != last && current_day != Some(day) {
+            response.push_str(&format!("## {}\n\n", day.format("%a, %b %d")));
+            current_day = Some(day);
         }
+        response.push_str(&format_event(event, display));
+    }
+    response
+}
 
-        response.push_str("\n\n");
+async fn handle_agenda_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    selection: &[String],
+    first: NaiveDate,
+    last: NaiveDate,
+    period: &str,
+) -> String {
+    if calendars.is_empty() {
+        return "No calendar configured".to_string();
+    }
+
+    let snapshot = match calendars.get(selection).await {
+        Ok(snapshot) => snapshot,
+        Err(_) => return "There was a problem fetching the calendar".to_string(),
+    };
+    let calendar = &snapshot.calendar;
+
+    let window_start = day_start(first, &config.display);
+    let window_end = day_start(last + Duration::days(1), &config.display);
+    let mut events = calendar.get_events_between(window_start, window_end);
+    events.retain(|event| should_show_event(event, &config.event_filtering));
+
+    if events.is_empty() {
+        return with_cache_note(format!("No events {}.", period), &snapshot, &config.display);
     }
 
+    let mut response = format_agenda(&events, first, last, period, &config.display);
+
     // Add info URL if configured
     if let Some(info_url) = &config.info_url {
         response.push_str(&format!("\nFor more information: {}\n", info_url));
     }
 
+    with_cache_note(response, &snapshot, &config.display)
+}
+
+/// Render a task as a markdown block. Tasks without a summary are skipped.
+fn format_task(task: &CalendarTask, now: DateTime<Utc>, display: &DisplayConfig) -> String {
+    let Some(summary) = &task.summary else {
+        return String::new();
+    };
+
+    let mut response = match &task.url {
+        Some(url) => format!("**[{}]({})**", summary, url),
+        None => format!("**{}**", summary),
+    };
+    if let Some(source) = &task.source {
+        response.push_str(&format!(" · {}", format_source(source)));
+    }
+    response.push('\n');
+
+    if let Some(due) = &task.due {
+        let overdue = if task.is_overdue(now) {
+            " (overdue)"
+        } else {
+            ""
+        };
+        response.push_str(&format!(
+            "* Due: {}{}\n",
+            format_event_time(due, display.timezone),
+            overdue
+        ));
+    }
+
+    if let Some(priority) = task.priority {
+        response.push_str(&format!("* Priority: {}\n", priority));
+    }
+
+    if let Some(percent) = task.percent_complete.filter(|p| *p > 0) {
+        response.push_str(&format!("* Progress: {}%\n", percent));
+    }
+
+    response.push_str("\n\n");
     response
 }
 
-async fn handle_meetings_events_request(config: &Config) -> String {
-    if config.webcal.is_empty() {
-        return "No webcal URL configured".to_string();
+async fn handle_tasks_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    selection: &[String],
+) -> String {
+    if calendars.is_empty() {
+        return "No calendar configured".to_string();
     }
 
-    let calendar = match IcalCalendar::from_url(&config.webcal).await {
-        Ok(calendar) => calendar,
+    let snapshot = match calendars.get(selection).await {
+        Ok(snapshot) => snapshot,
         Err(_) => return "There was a problem fetching the calendar".to_string(),
     };
+    let calendar = &snapshot.calendar;
 
-    let current_time = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
-    let upcoming_events = calendar.get_upcoming_events(&current_time);
+    let now = chrono::Utc::now();
+    let open_tasks = calendar.get_open_tasks();
 
-    if upcoming_events.is_empty() {
-        return "No upcoming events found.".to_string();
+    if open_tasks.is_empty() {
+        return with_cache_note(
+            "No open tasks found.".to_string(),
+            &snapshot,
+            &config.display,
+        );
     }
 
-    let mut response = String::new();
-    response.push_str("# Upcoming Meetings/Events\n\n");
+    let mut response = String::from("# Open Tasks\n\n");
+    for task in &open_tasks {
+        response.push_str(&format_task(task, now, &config.display));
+    }
 
-    for event in upcoming_events {
-        if let Some(summary) = &event.summary {
-            if let Some(url) = &event.url {
-                response.push_str(&format!("**[{}]({})**\n", summary, url));
-            } else {
-                response.push_str(&format!("**{}**\n", summary));
+    // Add info URL if configured
+    if let Some(info_url) = &config.info_url {
+        response.push_str(&format!("\nFor more information: {}\n", info_url));
+    }
+
+    with_cache_note(response, &snapshot, &config.display)
+}
+
+async fn handle_due_tasks_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    selection: &[String],
+) -> String {
+    if calendars.is_empty() {
+        return "No calendar configured".to_string();
+    }
+
+    let snapshot = match calendars.get(selection).await {
+        Ok(snapshot) => snapshot,
+        Err(_) => return "There was a problem fetching the calendar".to_string(),
+    };
+    let calendar = &snapshot.calendar;
+
+    let now = chrono::Utc::now();
+    let due_tasks = calendar.get_tasks_due_before(now + chrono::Duration::days(DUE_SOON_DAYS));
+
+    if due_tasks.is_empty() {
+        return with_cache_note("No tasks due soon.".to_string(), &snapshot, &config.display);
+    }
+
+    let mut response = String::from("# Tasks Due Soon\n\n");
+    for task in &due_tasks {
+        response.push_str(&format_task(task, now, &config.display));
+    }
+
+    // Add info URL if configured
+    if let Some(info_url) = &config.info_url {
+        response.push_str(&format!("\nFor more information: {}\n", info_url));
+    }
+
+    with_cache_note(response, &snapshot, &config.display)
+}
+
+/// Render the best search results, up to `max_events` of them.
+fn format_search_results(query: &str, results: &[SearchResult], display: &DisplayConfig) -> String {
+    let mut response = format!("# Events matching \"{}\"\n\n", query);
+    for result in results.iter().take(display.max_events) {
+        response.push_str(&format_event(&result.event, display));
+    }
+    if results.len() > display.max_events {
+        response.push_str(&format!(
+            "_…and {} more. Try a more specific search._\n",
+            results.len() - display.max_events
+        ));
+    }
+    response
+}
+
+async fn handle_search_request(
+    config: &Config,
+    calendars: &CalendarSet,
+    selection: &[String],
+    query: &str,
+    include_past: bool,
+) -> String {
+    if calendars.is_empty() {
+        return "No calendar configured".to_string();
+    }
+
+    let snapshot = match calendars.get(selection).await {
+        Ok(snapshot) => snapshot,
+        Err(_) => return "There was a problem fetching the calendar".to_string(),
+    };
+
+    let mut results = snapshot.calendar.search(query, Utc::now(), include_past);
+    // Events whose details are withheld must not be found by them either
+    results.retain(|result| {
+        should_show_event(&result.event, &config.event_filtering)
+            && !hides_details(&result.event, &config.display)
+    });
+
+    if results.is_empty() {
+        let response = format!("No events found matching \"{}\".", query);
+        return with_cache_note(response, &snapshot, &config.display);
+    }
+
+    let mut response = format_search_results(query, &results, &config.display);
+
+    // Add info URL if configured
+    if let Some(info_url) = &config.info_url {
+        response.push_str(&format!("\nFor more information: {}\n", info_url));
+    }
+
+    with_cache_note(response, &snapshot, &config.display)
+}
+
+/// Render the message sent when one of an event's alarms goes off.
+fn format_alarm(event: &CalendarEvent, display: &DisplayConfig) -> String {
+    format!("# Reminder\n\n{}", format_event(event, display))
+}
+
+/// Render the message sent the given time before an event starts.
+fn format_before_event(event: &CalendarEvent, before: Duration, display: &DisplayConfig) -> String {
+    format!(
+        "# Starting in {}\n\n{}",
+        format_offset(before),
+        format_event(event, display)
+    )
+}
+
+/// Render the message sent when an event starts, leading with its join link.
+fn format_event_start(event: &CalendarEvent, display: &DisplayConfig) -> String {
+    let mut response = String::from("# Starting now\n\n");
+    if let Some(link) = event.join_link()
+        && !hides_details(event, display)
+    {
+        response.push_str(&format!("**Join: {}**\n\n", link));
+    }
+    response.push_str(&format_event(event, display));
+    response
+}
+
+/// Render a reminder offset, e.g. "10 minutes" or "1 hour 30 minutes".
+fn format_offset(offset: Duration) -> String {
+    let units = [
+        (offset.num_days(), "day"),
+        (offset.num_hours() % 24, "hour"),
+        (offset.num_minutes() % 60, "minute"),
+    ];
+    let parts: Vec<String> = units
+        .into_iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" }))
+        .collect();
+
+    if parts.is_empty() {
+        "less than a minute".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Whether any reminder is timed by event starts instead of by cron.
+fn has_event_reminders(config: &Config) -> bool {
+    config.reminders.iter().any(|r| r.cron.is_none())
+}
+
+/// A message the event scheduler sends at a given time.
+struct PlannedMessage {
+    time: DateTime<Utc>,
+    room_id: String,
+    message: String,
+    mention_room: bool,
+}
+
+/// Messages tied to event times that are due after `after` and no later than
+/// `until`: VALARM triggers and event-relative reminders, in send order.
+fn plan_event_messages(
+    calendar: &IcalCalendar,
+    config: &Config,
+    after: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<PlannedMessage> {
+    let mut plan = Vec::new();
+
+    if let Some(room_id) = &config.alarms.matrix_room {
+        let room_calendars = calendars_for_room(room_id, &config.room_calendars);
+        for (time, event) in calendar.alarms_between(after, until) {
+            if should_show_event(&event, &config.event_filtering)
+                && event_in_calendars(&event, room_calendars)
+            {
+                plan.push(PlannedMessage {
+                    time,
+                    room_id: room_id.clone(),
+                    message: format_alarm(&event, &config.display),
+                    mention_room: false,
+                });
             }
+        }
+    }
 
-            if let Some(start_time) = &event.start_time {
-                response.push_str(&format!("* Starts: {}\n", format_ical_date(start_time)));
+    for reminder in &config.reminders {
+        let before = match reminder.reminder_type {
+            ReminderType::BeforeEvent(before) => before,
+            ReminderType::EventStart => Duration::zero(),
+            _ => continue,
+        };
+        let reminder_calendars = calendars_for_reminder(reminder, &config.room_calendars);
+        for event in calendar.starts_between(after + before, until + before) {
+            if !should_show_event(&event, &config.event_filtering)
+                || !event_in_calendars(&event, reminder_calendars)
+            {
+                continue;
             }
+            let Some(start) = event.start_time else {
+                continue;
+            };
+            let message = match reminder.reminder_type {
+                ReminderType::EventStart => format_event_start(&event, &config.display),
+                _ => format_before_event(&event, before, &config.display),
+            };
+            plan.push(PlannedMessage {
+                time: start.to_utc() - before,
+                room_id: reminder.matrix_room.clone(),
+                message,
+                mention_room: reminder.mention_room,
+            });
+        }
+    }
 
-            if let Some(end_time) = &event.end_time {
-                response.push_str(&format!("* Ends: {}\n", format_ical_date(end_time)));
+    plan.sort_by_key(|planned| planned.time);
+    plan
+}
+
+/// Send messages tied to event times. The cached calendar is read again
+/// every interval and the messages due before the next read are planned from
+/// it, so new, moved and removed events are picked up.
+async fn run_event_scheduler(client: Client, config: Config, calendars: Arc<CalendarSet>) {
+    let mut planned_until = chrono::Utc::now();
+
+    loop {
+        let plan_until = chrono::Utc::now() + EVENT_SCHEDULER_INTERVAL;
+
+        match calendars.get(&[]).await {
+            Ok(snapshot) => {
+                let calendar = &snapshot.calendar;
+                for planned in plan_event_messages(calendar, &config, planned_until, plan_until) {
+                    // Skip messages missed while the calendar could not be fetched
+                    if planned.time + EVENT_SCHEDULER_INTERVAL < chrono::Utc::now() {
+                        continue;
+                    }
+                    sleep_until(planned.time).await;
+                    let content = room_message(planned.message, planned.mention_room);
+                    send_room_message(&client, &planned.room_id, content).await;
+                }
+                planned_until = plan_until;
             }
+            Err(e) => eprintln!("Failed to fetch calendar for event reminders: {}", e),
+        }
+
+        sleep_until(plan_until).await;
+    }
+}
+
+/// Render the message announcing a change to the calendar. A change to
+/// several occurrences is announced for the series, by its first occurrence.
+fn format_change(series: &SeriesChange, display: &DisplayConfig) -> String {
+    let count = series.occurrences;
+    match (&series.change, count > 1) {
+        (CalendarChange::Added(event), false) => {
+            format!("# New event\n\n{}", format_event(event, display))
+        }
+        (CalendarChange::Added(event), true) => format!(
+            "# New recurring event\n\n{} upcoming occurrences, starting with:\n\n{}",
+            count,
+            format_event(event, display)
+        ),
+        (CalendarChange::Rescheduled { before, after }, false) => format!(
+            "# Event rescheduled\n\nRescheduled from {} to {}\n\n{}",
+            format_start(before, display),
+            format_start(after, display),
+            format_event(after, display)
+        ),
+        (CalendarChange::Rescheduled { before, after }, true) => format!(
+            "# Series rescheduled\n\nSeries moved from {} to {} ({} upcoming occurrences)\n\n{}",
+            format_start(before, display),
+            format_start(after, display),
+            count,
+            format_event(after, display)
+        ),
+        (CalendarChange::LocationChanged { before, after }, many)
+            if !hides_details(after, display) =>
+        {
+            let occurrences = if many {
+                format!(" of {} upcoming occurrences", count)
+            } else {
+                String::new()
+            };
+            format!(
+                "# Location changed\n\nLocation{} changed from {} to {}\n\n{}",
+                occurrences,
+                before.location.as_deref().unwrap_or("none"),
+                after.location.as_deref().unwrap_or("none"),
+                format_event(after, display)
+            )
+        }
+        (CalendarChange::LocationChanged { after, .. }, _) => {
+            format!("# Location changed\n\n{}", format_event(after, display))
+        }
+        (CalendarChange::Cancelled(event), false) => {
+            format!("# Event cancelled\n\n{}", format_event(event, display))
+        }
+        (CalendarChange::Cancelled(event), true) => format!(
+            "# Series cancelled\n\n{} upcoming occurrences cancelled, starting with:\n\n{}",
+            count,
+            format_event(event, display)
+        ),
+    }
+}
+
+/// Render when an event starts, as a date span for all-day events.
+fn format_start(event: &CalendarEvent, display: &DisplayConfig) -> String {
+    match (event.all_day_span(), &event.start_time) {
+        (Some((first_day, last_day)), _) => format_all_day_span(first_day, last_day),
+        (None, Some(start)) => {
+            format_event_time(start, display_zone(event.start_tzid.as_deref(), display))
+        }
+        (None, None) => "an unknown time".to_string(),
+    }
+}
 
-            if let Some(location) = &event.location {
-                response.push_str(&format!("* Location: {}\n", location));
+/// Check the cached calendar and announce changes since the previous
+/// snapshot to the subscribed rooms. Only upcoming events are compared.
+async fn run_change_notifier(client: Client, config: Config, calendars: Arc<CalendarSet>) {
+    let settings = &config.change_notifications;
+    let mut interval = tokio::time::interval(
+        settings
+            .poll_interval
+            .to_std()
+            .unwrap_or(std::time::Duration::from_secs(300)),
+    );
+    let mut previous: Option<CalendarSnapshot> = None;
+
+    loop {
+        interval.tick().await;
+
+        let current = match calendars.get(&[]).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                eprintln!("Failed to fetch calendar for change notifications: {}", e);
+                continue;
             }
+        };
 
-            response.push_str("\n\n");
+        // An unchanged single feed keeps the same parsed calendar
+        if let Some(previous) = &previous
+            && !Arc::ptr_eq(&previous.calendar, &current.calendar)
+        {
+            let now = chrono::Utc::now();
+            let window_end = now + Duration::days(CHANGE_WINDOW_DAYS);
+            for series in diff_calendars(&previous.calendar, &current.calendar, now, window_end) {
+                // Cancelled events are compared as they were before
+                let event = series.change.event();
+                if !should_show_event(event, &config.event_filtering) {
+                    continue;
+                }
+                let message = format_change(&series, &config.display);
+                for room_id in &settings.matrix_rooms {
+                    let room_calendars = calendars_for_room(room_id, &config.room_calendars);
+                    if event_in_calendars(event, room_calendars) {
+                        let content = room_message(message.clone(), false);
+                        send_room_message(&client, room_id, content).await;
+                    }
+                }
+            }
         }
+        previous = Some(current);
     }
+}
 
-    // Add info URL if configured
-    if let Some(info_url) = &config.info_url {
-        response.push_str(&format!("\nFor more information: {}\n", info_url));
+/// Sleep until the given time; returns immediately if it has passed.
+async fn sleep_until(time: DateTime<Utc>) {
+    if let Ok(delay) = (time - chrono::Utc::now()).to_std() {
+        tokio::time::sleep(delay).await;
     }
+}
 
-    response
+/// Build a markdown message, mentioning @room if requested.
+fn room_message(message: String, mention_room: bool) -> RoomMessageEventContent {
+    if mention_room {
+        RoomMessageEventContent::text_markdown(format!("@room {}", message))
+            .add_mentions(Mentions::with_room_mention())
+    } else {
+        RoomMessageEventContent::text_markdown(message)
+    }
+}
+
+/// Send a message to a room the bot has joined, logging failures.
+async fn send_room_message(client: &Client, room_id: &str, content: RoomMessageEventContent) {
+    let room_id = match RoomId::parse(room_id) {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("Invalid room ID '{}': {}", room_id, e);
+            return;
+        }
+    };
+
+    let Some(room) = client.get_room(&room_id) else {
+        eprintln!("Bot is not in room '{}'", room_id);
+        return;
+    };
+
+    if let Err(e) = room.send(content).await {
+        eprintln!("Failed to send message to room '{}': {}", room_id, e);
+    }
 }
 
 pub fn validate_reminders(config: &Config) -> Result<()> {
     for (i, reminder) in config.reminders.iter().enumerate() {
         // Validate cron expression
-        if let Err(e) = Job::new_async(&reminder.cron, move |_uuid, _l| Box::pin(async {})) {
+        if let Some(cron) = &reminder.cron
+            && let Err(e) = Job::new_async(cron, move |_uuid, _l| Box::pin(async {}))
+        {
             return Err(anyhow::anyhow!(
                 "Invalid cron expression in reminder #{}: '{}'. Error: {}",
                 i + 1,
-                reminder.cron,
+                cron,
                 e
             ));
         }
@@ -370,6 +1391,36 @@ pub fn validate_reminders(config: &Config) -> Result<()> {
         }
     }
 
+    if let Some(room) = &config.alarms.matrix_room
+        && let Err(e) = RoomId::parse(room)
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid Matrix room ID for alarms: '{}'. Error: {}",
+            room,
+            e
+        ));
+    }
+
+    for room in config.room_calendars.rooms.keys() {
+        if let Err(e) = RoomId::parse(room) {
+            return Err(anyhow::anyhow!(
+                "Invalid Matrix room ID in room_calendars: '{}'. Error: {}",
+                room,
+                e
+            ));
+        }
+    }
+
+    for room in &config.change_notifications.matrix_rooms {
+        if let Err(e) = RoomId::parse(room) {
+            return Err(anyhow::anyhow!(
+                "Invalid Matrix room ID for change notifications: '{}'. Error: {}",
+                room,
+                e
+            ));
+        }
+    }
+
     if config.reminders.is_empty() {
         println!("No reminders configured");
     } else {
@@ -379,43 +1430,57 @@ pub fn validate_reminders(config: &Config) -> Result<()> {
     Ok(())
 }
 
-async fn setup_reminder_scheduler(client: &Client, config: &Config) -> Result<()> {
+async fn setup_reminder_scheduler(
+    client: &Client,
+    config: &Config,
+    calendars: &Arc<CalendarSet>,
+) -> Result<()> {
     let scheduler = JobScheduler::new().await?;
 
+    let mut jobs = 0;
+
     for (i, reminder) in config.reminders.iter().enumerate() {
+        // Event-relative reminders are sent by the event scheduler
+        let Some(cron) = &reminder.cron else {
+            continue;
+        };
+
         let client_clone = client.clone();
         let config_clone = config.clone();
-        let reminder_type = reminder.reminder_type.clone();
-        let room_id = reminder.matrix_room.clone();
+        let calendars_clone = Arc::clone(calendars);
+        let reminder_clone = reminder.clone();
 
-        let job = Job::new_async(&reminder.cron, move |_uuid, _l| {
+        let job = Job::new_async(cron, move |_uuid, _l| {
             let client_clone = client_clone.clone();
             let config_clone = config_clone.clone();
-            let room_id = room_id.clone();
-            let reminder_type = reminder_type.clone();
+            let calendars_clone = Arc::clone(&calendars_clone);
+            let reminder_clone = reminder_clone.clone();
 
             Box::pin(async move {
-                send_scheduled_reminder(&client_clone, &config_clone, &room_id, &reminder_type)
-                    .await;
+                send_scheduled_reminder(
+                    &client_clone,
+                    &config_clone,
+                    &calendars_clone,
+                    &reminder_clone,
+                )
+                .await;
             })
         })?;
 
         scheduler.add(job).await?;
+        jobs += 1;
         println!(
             "Scheduled reminder #{}: {} -> {:?} in room {}",
             i + 1,
-            reminder.cron,
+            cron,
             reminder.reminder_type,
             reminder.matrix_room
         );
     }
 
-    if !config.reminders.is_empty() {
+    if jobs > 0 {
         scheduler.start().await?;
-        println!(
-            "Reminder scheduler started with {} jobs",
-            config.reminders.len()
-        );
+        println!("Reminder scheduler started with {} jobs", jobs);
     }
 
     Ok(())
@@ -424,13 +1489,13 @@ async fn setup_reminder_scheduler(client: &Client, config: &Config) -> Result<()
 async fn send_scheduled_reminder(
     client: &Client,
     config: &Config,
-    room_id: &str,
-    reminder_type: &ReminderType,
+    calendars: &CalendarSet,
+    reminder: &ReminderConfig,
 ) {
-    let room_id = match RoomId::parse(room_id) {
+    let room_id = match RoomId::parse(&reminder.matrix_room) {
         Ok(id) => id,
         Err(e) => {
-            eprintln!("Invalid room ID '{}': {}", room_id, e);
+            eprintln!("Invalid room ID '{}': {}", reminder.matrix_room, e);
             return;
         }
     };
@@ -443,12 +1508,28 @@ async fn send_scheduled_reminder(
         }
     };
 
-    let message = match reminder_type {
-        ReminderType::NextMeeting => handle_meeting_event_request(config).await,
-        ReminderType::AllUpcomingMeetings => handle_meetings_events_request(config).await,
+    let selection = calendars_for_reminder(reminder, &config.room_calendars);
+    let message = match reminder.reminder_type {
+        ReminderType::NextMeeting => {
+            handle_meeting_event_request(config, calendars, selection).await
+        }
+        ReminderType::AllUpcomingMeetings => {
+            let page = PageCursor {
+                selection: selection.to_vec(),
+                since: Utc::now(),
+                offset: 0,
+                page_size: config.display.max_events,
+            };
+            handle_meetings_events_request(config, calendars, &page, false)
+                .await
+                .0
+        }
+        ReminderType::DueTasks => handle_due_tasks_request(config, calendars, selection).await,
+        // Sent by the event scheduler, never from a cron job
+        ReminderType::BeforeEvent(_) | ReminderType::EventStart => return,
     };
 
-    let response = RoomMessageEventContent::text_markdown(message);
+    let response = room_message(message, reminder.mention_room);
 
     if let Err(e) = room.send(response).await {
         eprintln!(
@@ -467,7 +1548,567 @@ async fn send_scheduled_reminder(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, ReminderConfig, ReminderType};
+    use matrix_bot_ical::config::{Config, ReminderConfig, ReminderType};
+
+    #[test]
+    fn test_format_event_time() {
+        let instant: EventTime = "20251203T130000Z".parse().unwrap();
+        assert_eq!(
+            format_event_time(&instant, None),
+            "Wed, Dec 03, 2025 at 01:00 PM UTC"
+        );
+        assert_eq!(
+            format_event_time(&instant, Some(chrono_tz::Asia::Tokyo)),
+            "Wed, Dec 03, 2025 at 10:00 PM JST"
+        );
+        let floating: EventTime = "20251203T100000".parse().unwrap();
+        assert_eq!(
+            format_event_time(&floating, Some(chrono_tz::Asia::Tokyo)),
+            "Wed, Dec 03, 2025 at 10:00 AM"
+        );
+        let date: EventTime = "20251224".parse().unwrap();
+        assert_eq!(format_event_time(&date, None), "Wed, Dec 24, 2025");
+    }
+
+    #[test]
+    fn test_format_event_in_its_time_zone() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Release retro
+            DTSTART;TZID=America/New_York:20251203T090000
+            DTEND;TZID=America/New_York:20251203T100000
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let event = &calendar.events[0];
+
+        let local = format_event(event, &DisplayConfig::default());
+        assert!(local.contains("* Starts: Wed, Dec 03, 2025 at 09:00 AM EST\n"));
+        assert!(local.contains("* Ends: Wed, Dec 03, 2025 at 10:00 AM EST\n"));
+
+        // A configured zone applies to every event
+        let display = DisplayConfig {
+            timezone: Some(chrono_tz::Europe::Berlin),
+            ..DisplayConfig::default()
+        };
+        assert!(format_event(event, &display).contains("at 03:00 PM CET\n"));
+    }
+
+    #[test]
+    fn test_format_all_day_events() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 12, d).unwrap();
+        assert_eq!(format_all_day_span(day(24), day(24)), "All day, Wed Dec 24");
+        assert_eq!(format_all_day_span(day(24), day(26)), "Dec 24 – Dec 26");
+        assert_eq!(
+            format_all_day_span(day(30), NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()),
+            "Dec 30, 2025 – Jan 2, 2026"
+        );
+    }
+
+    #[test]
+    fn test_format_event_status() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            STATUS:CANCELLED
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Lunch
+            DTSTART:20251201T120000Z
+            STATUS:TENTATIVE
+            TRANSP:TRANSPARENT
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+
+        assert!(
+            format_event(&calendar.events[0], &DisplayConfig::default())
+                .starts_with("~~**Standup**~~ (cancelled)\n")
+        );
+        assert!(
+            format_event(&calendar.events[1], &DisplayConfig::default())
+                .starts_with("**Lunch** (tentative)\n* Shows as: free\n")
+        );
+    }
+
+    #[test]
+    fn test_format_attendance() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Planning
+            DTSTART:20251201T100000Z
+            ORGANIZER;CN=Alice:mailto:alice@example.com
+            ATTENDEE;PARTSTAT=ACCEPTED:mailto:bob@example.com
+            ATTENDEE;PARTSTAT=ACCEPTED:mailto:carol@example.com
+            ATTENDEE;PARTSTAT=TENTATIVE:mailto:dave@example.com
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let event = &calendar.events[0];
+
+        assert_eq!(
+            format_attendance(event).unwrap(),
+            "Organizer: Alice — 2 accepted, 1 tentative"
+        );
+        assert!(!format_event(event, &DisplayConfig::default()).contains("Organizer"));
+        let display = DisplayConfig {
+            show_attendance: true,
+            ..Default::default()
+        };
+        assert!(
+            format_event(event, &display)
+                .contains("* Organizer: Alice — 2 accepted, 1 tentative\n")
+        );
+    }
+
+    #[test]
+    fn test_format_event_source() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            STATUS:TENTATIVE
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let source = EventSource {
+            name: "team".to_string(),
+            color: Some("blue".to_string()),
+        };
+        let merged = IcalCalendar::merge([(&source, &calendar)]);
+
+        assert!(
+            format_event(&merged.events[0], &DisplayConfig::default())
+                .starts_with("**Standup** (tentative) · 🟦 team\n")
+        );
+        let uncolored = EventSource {
+            color: None,
+            ..source
+        };
+        assert_eq!(format_source(&uncolored), "team");
+    }
+
+    #[test]
+    fn test_format_private_event() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Doctor
+            DTSTART:20251201T100000Z
+            LOCATION:Clinic
+            CLASS:PRIVATE
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let event = &calendar.events[0];
+
+        assert_eq!(
+            format_event(event, &DisplayConfig::default()),
+            "**Private event**\n* Starts: Mon, Dec 01, 2025 at 10:00 AM UTC\n\n\n"
+        );
+        let display = DisplayConfig {
+            show_private_details: true,
+            ..Default::default()
+        };
+        assert!(format_event(event, &display).contains("**Doctor**\n"));
+        assert!(format_event(event, &display).contains("* Location: Clinic\n"));
+    }
+
+    #[test]
+    fn test_format_task() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VTODO
+            SUMMARY:Write release notes
+            DUE:20251205T170000Z
+            PRIORITY:1
+            PERCENT-COMPLETE:40
+            END:VTODO
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let task = &calendar.tasks[0];
+
+        let before_due = "2025-12-04T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            format_task(task, before_due, &DisplayConfig::default()),
+            "**Write release notes**\n* Due: Fri, Dec 05, 2025 at 05:00 PM UTC\n* Priority: 1\n* Progress: 40%\n\n\n"
+        );
+        let after_due = "2025-12-06T00:00:00Z".parse().unwrap();
+        assert!(
+            format_task(task, after_due, &DisplayConfig::default())
+                .contains("05:00 PM UTC (overdue)\n")
+        );
+    }
+
+    #[test]
+    fn test_format_event_start() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            LOCATION:https://meet.example.com/standup
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+
+        assert!(
+            format_event_start(&calendar.events[0], &DisplayConfig::default()).starts_with(
+                "# Starting now\n\n**Join: https://meet.example.com/standup**\n\n**Standup**\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_change() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:review@example.com
+            SUMMARY:Review
+            DTSTART:20251201T100000Z
+            LOCATION:Room 1
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:review@example.com
+            SUMMARY:Review
+            DTSTART:20251202T140000Z
+            LOCATION:Room 2
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let before = calendar.events[0].clone();
+        let after = calendar.events[1].clone();
+        let display = DisplayConfig::default();
+
+        let once = |change| SeriesChange {
+            change,
+            occurrences: 1,
+        };
+
+        let rescheduled = format_change(
+            &once(CalendarChange::Rescheduled {
+                before: before.clone(),
+                after: after.clone(),
+            }),
+            &display,
+        );
+        assert!(rescheduled.starts_with(
+            "# Event rescheduled\n\nRescheduled from Mon, Dec 01, 2025 at 10:00 AM UTC to Tue, Dec 02, 2025 at 02:00 PM UTC\n\n**Review**\n"
+        ));
+
+        let series = format_change(
+            &SeriesChange {
+                change: CalendarChange::Rescheduled {
+                    before: before.clone(),
+                    after: after.clone(),
+                },
+                occurrences: 5,
+            },
+            &display,
+        );
+        assert!(series.starts_with(
+            "# Series rescheduled\n\nSeries moved from Mon, Dec 01, 2025 at 10:00 AM UTC to Tue, Dec 02, 2025 at 02:00 PM UTC (5 upcoming occurrences)\n"
+        ));
+
+        let moved = format_change(
+            &once(CalendarChange::LocationChanged {
+                before: before.clone(),
+                after,
+            }),
+            &display,
+        );
+        assert!(
+            moved.starts_with("# Location changed\n\nLocation changed from Room 1 to Room 2\n")
+        );
+
+        let cancelled = format_change(
+            &SeriesChange {
+                change: CalendarChange::Cancelled(before),
+                occurrences: 3,
+            },
+            &display,
+        );
+        assert!(cancelled.starts_with(
+            "# Series cancelled\n\n3 upcoming occurrences cancelled, starting with:\n\n**Review**\n"
+        ));
+    }
+
+    #[test]
+    fn test_cache_note() {
+        let mut snapshot = CalendarSnapshot {
+            calendar: Arc::new(
+                IcalCalendar::parse_ical_content("BEGIN:VCALENDAR\nEND:VCALENDAR\n").unwrap(),
+            ),
+            fetched_at: "2025-12-01T10:32:00Z".parse().unwrap(),
+            etag: None,
+            last_modified: None,
+            outdated: false,
+        };
+        let display = DisplayConfig::default();
+        let now = "2025-12-01T11:00:00Z".parse().unwrap();
+        assert_eq!(cache_note(&snapshot, now, &display), None);
+
+        snapshot.outdated = true;
+        assert_eq!(
+            cache_note(&snapshot, now, &display).as_deref(),
+            Some("(cached as of 10:32 UTC)")
+        );
+        let next_day = "2025-12-02T09:00:00Z".parse().unwrap();
+        assert_eq!(
+            cache_note(&snapshot, next_day, &display).as_deref(),
+            Some("(cached as of Dec 1, 10:32 UTC)")
+        );
+
+        let berlin = DisplayConfig {
+            timezone: Some(chrono_tz::Europe::Berlin),
+            ..DisplayConfig::default()
+        };
+        assert_eq!(
+            cache_note(&snapshot, now, &berlin).as_deref(),
+            Some("(cached as of 11:32 CET)")
+        );
+        // Already the next day in Berlin
+        let late = "2025-12-01T23:30:00Z".parse().unwrap();
+        assert_eq!(
+            cache_note(&snapshot, late, &berlin).as_deref(),
+            Some("(cached as of Dec 1, 11:32 CET)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_every_command_is_handled() {
+        let config = Config::from_toml(indoc::indoc! {r#"
+            homeserver = "https://matrix.example.com"
+            username = "@bot:example.com"
+            access_token = "secret_token"
+        "#})
+        .unwrap();
+        let commands = bot_commands();
+        let room_id = RoomId::parse("!room:example.com").unwrap();
+        let sender = UserId::parse("@alice:example.com").unwrap();
+        let cursors = PageCursors::new();
+
+        for spec in commands.commands() {
+            let command = ParsedCommand {
+                name: spec.name,
+                args: Vec::new(),
+            };
+            let response = handle_command(
+                &command,
+                &room_id,
+                &sender,
+                &config,
+                &CalendarSet::new(),
+                &commands,
+                &cursors,
+            )
+            .await;
+            assert!(!response.contains("is not available"), "!{}", spec.name);
+        }
+    }
+
+    #[test]
+    fn test_format_agenda() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Conference
+            DTSTART:20251130T090000Z
+            DTEND:20251202T170000Z
+            END:VEVENT
+            BEGIN:VEVENT
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=3
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 12, d).unwrap();
+        let events = calendar.get_events_between(
+            day(1).and_time(NaiveTime::MIN).and_utc(),
+            day(3).and_time(NaiveTime::MIN).and_utc(),
+        );
+
+        let period = format_period(day(1), day(2));
+        assert_eq!(period, "from Mon, Dec 01, 2025 to Tue, Dec 02, 2025");
+        let agenda = format_agenda(&events, day(1), day(2), &period, &DisplayConfig::default());
+        let lines: Vec<_> = agenda
+            .lines()
+            .filter(|line| line.starts_with('#') || line.starts_with("**"))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "# Events from Mon, Dec 01, 2025 to Tue, Dec 02, 2025",
+                "## Mon, Dec 01",
+                "**Conference**",
+                "**Standup**",
+                "## Tue, Dec 02",
+                "**Standup**",
+            ]
+        );
+
+        let single_day = format_agenda(&events, day(1), day(1), "today", &DisplayConfig::default());
+        assert!(single_day.starts_with("# Events today\n\n**Conference**\n"));
+        assert!(!single_day.contains("## "));
+    }
+
+    #[test]
+    fn test_agenda_days_in_display_zone() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Late call
+            DTSTART:20251201T233000Z
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let display = DisplayConfig {
+            timezone: Some(chrono_tz::Europe::Berlin),
+            ..DisplayConfig::default()
+        };
+        let day = |d| NaiveDate::from_ymd_opt(2025, 12, d).unwrap();
+
+        assert_eq!(
+            day_start(day(2), &display),
+            "2025-12-01T23:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            local_date("2025-12-01T23:30:00Z".parse().unwrap(), &display),
+            day(2)
+        );
+
+        let events =
+            calendar.get_events_between(day_start(day(1), &display), day_start(day(3), &display));
+        let agenda = format_agenda(&events, day(1), day(2), "", &display);
+        assert!(agenda.contains("## Tue, Dec 02\n\n**Late call**"));
+    }
+
+    #[test]
+    fn test_format_meetings_page() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=5
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let since = "2025-11-30T12:00:00Z".parse().unwrap();
+        let upcoming = calendar.get_upcoming_events(since);
+        let page = |offset| PageCursor {
+            selection: Vec::new(),
+            since,
+            offset,
+            page_size: 2,
+        };
+        let display = DisplayConfig::default();
+        let titles = |response: &str| response.matches("**Standup**").count();
+
+        let (first, remaining) = format_meetings_page(&[], &upcoming, &page(0), true, &display);
+        assert_eq!(remaining, 3);
+        assert_eq!(titles(&first), 2);
+        assert!(first.starts_with("# Upcoming Meetings/Events\n"));
+        assert!(first.ends_with("_…and 3 more. Send `!more` for the next 2._\n"));
+
+        let (last, remaining) = format_meetings_page(&[], &upcoming, &page(4), true, &display);
+        assert_eq!(remaining, 0);
+        assert_eq!(titles(&last), 1);
+        assert!(last.starts_with("# More Meetings/Events\n"));
+        assert!(!last.contains("!more"));
+
+        let (reminder, _) = format_meetings_page(&[], &upcoming, &page(0), false, &display);
+        assert!(reminder.ends_with("_…and 3 more._\n"));
+    }
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(Duration::minutes(10)), "10 minutes");
+        assert_eq!(format_offset(Duration::minutes(90)), "1 hour 30 minutes");
+        assert_eq!(format_offset(Duration::days(1)), "1 day");
+        assert_eq!(format_offset(Duration::seconds(30)), "less than a minute");
+    }
+
+    #[test]
+    fn test_plan_event_messages() {
+        let calendar = IcalCalendar::parse_ical_content(indoc::indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:standup@example.com
+            SUMMARY:Standup
+            DTSTART:20251201T100000Z
+            RRULE:FREQ=DAILY;COUNT=2
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            TRIGGER:-PT5M
+            END:VALARM
+            END:VEVENT
+            END:VCALENDAR
+        "})
+        .unwrap();
+        let mut config = Config::from_toml(indoc::indoc! {r#"
+            homeserver = "https://matrix.example.com"
+            username = "@bot:example.com"
+            access_token = "secret_token"
+
+            [alarms]
+            matrix_room = "!alarms:example.com"
+
+            [[reminders]]
+            reminder_type = "BeforeEvent"
+            before = "10m"
+            matrix_room = "!team:example.com"
+        "#})
+        .unwrap();
+
+        let after = "2025-12-01T09:00:00Z".parse().unwrap();
+        let until = "2025-12-01T10:00:00Z".parse().unwrap();
+        let plan = plan_event_messages(&calendar, &config, after, until);
+        let summary: Vec<(String, &str)> = plan
+            .iter()
+            .map(|p| (p.time.format("%H:%M").to_string(), p.room_id.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("09:50".to_string(), "!team:example.com"),
+                ("09:55".to_string(), "!alarms:example.com"),
+            ]
+        );
+        assert!(
+            plan[0]
+                .message
+                .starts_with("# Starting in 10 minutes\n\n**Standup**")
+        );
+
+        // Nothing is planned twice across consecutive windows
+        let next = "2025-12-01T11:00:00Z".parse().unwrap();
+        assert!(plan_event_messages(&calendar, &config, until, next).is_empty());
+
+        config.alarms.matrix_room = None;
+        assert_eq!(
+            plan_event_messages(&calendar, &config, after, until).len(),
+            1
+        );
+    }
 
     #[test]
     fn test_validate_reminders_valid_config() {
@@ -475,15 +2116,23 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![ReminderConfig {
-                cron: "0 9 * * MON-FRI".to_string(), // Valid cron: weekdays at 9 AM
+                cron: Some("0 9 * * MON-FRI".to_string()), // Valid cron: weekdays at 9 AM
                 matrix_room: "!roomid:example.com".to_string(),
                 reminder_type: ReminderType::NextMeeting,
+                mention_room: false,
+                calendars: Vec::new(),
             }],
         };
 
@@ -502,15 +2151,23 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![ReminderConfig {
-                cron: "0 9 * * MON".to_string(), // Valid cron: monday at 9 AM
+                cron: Some("0 9 * * MON".to_string()), // Valid cron: monday at 9 AM
                 matrix_room: "!roomid:example.com".to_string(),
                 reminder_type: ReminderType::NextMeeting,
+                mention_room: false,
+                calendars: Vec::new(),
             }],
         };
 
@@ -529,15 +2186,23 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![ReminderConfig {
-                cron: "0 9 * * MON#2".to_string(), // Valid cron: second monday at 9 AM
+                cron: Some("0 9 * * MON#2".to_string()), // Valid cron: second monday at 9 AM
                 matrix_room: "!roomid:example.com".to_string(),
                 reminder_type: ReminderType::NextMeeting,
+                mention_room: false,
+                calendars: Vec::new(),
             }],
         };
 
@@ -556,15 +2221,23 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![ReminderConfig {
-                cron: "invalid cron expression".to_string(),
+                cron: Some("invalid cron expression".to_string()),
                 matrix_room: "!roomid:example.com".to_string(),
                 reminder_type: ReminderType::NextMeeting,
+                mention_room: false,
+                calendars: Vec::new(),
             }],
         };
 
@@ -577,15 +2250,23 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![ReminderConfig {
-                cron: "0 9 * * 1-5".to_string(),
+                cron: Some("0 9 * * 1-5".to_string()),
                 matrix_room: "invalid-room-id".to_string(),
                 reminder_type: ReminderType::NextMeeting,
+                mention_room: false,
+                calendars: Vec::new(),
             }],
         };
 
@@ -598,11 +2279,17 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![],
         };
 
@@ -615,26 +2302,38 @@ mod tests {
             homeserver: "https://matrix.example.com".to_string(),
             username: "@bot:example.com".to_string(),
             access_token: "test_token".to_string(),
-            webcal: "https://example.com/calendar.ics".to_string(),
+            calendars: Vec::new(),
+            room_calendars: Default::default(),
             info_url: None,
             log_file: "/tmp/bot.log".to_string(),
             working_dir: "/tmp".to_string(),
+            refresh_interval: chrono::Duration::minutes(5),
             bot_filtering: Default::default(),
+            event_filtering: Default::default(),
+            display: Default::default(),
+            alarms: Default::default(),
+            change_notifications: Default::default(),
             reminders: vec![
                 ReminderConfig {
-                    cron: "0 9 * * MON-FRI".to_string(), // Weekdays at 9 AM
+                    cron: Some("0 9 * * MON-FRI".to_string()), // Weekdays at 9 AM
                     matrix_room: "!room1:example.com".to_string(),
                     reminder_type: ReminderType::NextMeeting,
+                    mention_room: false,
+                    calendars: Vec::new(),
                 },
                 ReminderConfig {
-                    cron: "0 17 * * MON-FRI".to_string(), // Weekdays at 5 PM
+                    cron: Some("0 17 * * MON-FRI".to_string()), // Weekdays at 5 PM
                     matrix_room: "!room2:example.com".to_string(),
                     reminder_type: ReminderType::AllUpcomingMeetings,
+                    mention_room: false,
+                    calendars: Vec::new(),
                 },
                 ReminderConfig {
-                    cron: "@daily".to_string(), // Daily at midnight
+                    cron: Some("@daily".to_string()), // Daily at midnight
                     matrix_room: "!room3:example.com".to_string(),
                     reminder_type: ReminderType::NextMeeting,
+                    mention_room: false,
+                    calendars: Vec::new(),
                 },
             ],
         };
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How long a listing can be continued with `!more`.
+const CURSOR_LIFETIME: Duration = Duration::hours(1);
+
+/// Where a paged listing of upcoming events left off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    /// Calendars the listing reads
+    pub selection: Vec<String>,
+    /// Time the listing was first requested; later pages list events after it
+    pub since: DateTime<Utc>,
+    /// Number of events already shown
+    pub offset: usize,
+    /// Number of events per page
+    pub page_size: usize,
+}
+
+/// Room and user a listing belongs to.
+type CursorKey = (String, String);
+
+/// The latest paged listing of each user in each room, so `!more` continues
+/// the listing that user asked for in that room.
+#[derive(Default)]
+pub struct PageCursors {
+    cursors: Mutex<HashMap<CursorKey, (PageCursor, DateTime<Utc>)>>,
+}
+
+impl PageCursors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember where a user's listing in a room left off, replacing any
+    /// earlier one.
+    pub fn set(&self, room_id: &str, user_id: &str, cursor: PageCursor, now: DateTime<Utc>) {
+        let mut cursors = self.cursors.lock().expect("page cursors lock");
+        cursors.retain(|_, (_, saved_at)| now - *saved_at < CURSOR_LIFETIME);
+        cursors.insert((room_id.to_string(), user_id.to_string()), (cursor, now));
+    }
+
+    /// Take the cursor of a user's listing in a room, if it has not expired.
+    pub fn take(&self, room_id: &str, user_id: &str, now: DateTime<Utc>) -> Option<PageCursor> {
+        let mut cursors = self.cursors.lock().expect("page cursors lock");
+        let (cursor, saved_at) = cursors.remove(&(room_id.to_string(), user_id.to_string()))?;
+        (now - saved_at < CURSOR_LIFETIME).then_some(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursors_are_kept_per_room_and_user() {
+        let now: DateTime<Utc> = "2025-12-01T10:00:00Z".parse().unwrap();
+        let cursor = PageCursor {
+            selection: vec!["team".to_string()],
+            since: now,
+            offset: 10,
+            page_size: 10,
+        };
+        let cursors = PageCursors::new();
+        cursors.set("!a:example.com", "@alice:example.com", cursor.clone(), now);
+
+        assert_eq!(
+            cursors.take("!b:example.com", "@alice:example.com", now),
+            None
+        );
+        assert_eq!(
+            cursors.take("!a:example.com", "@bob:example.com", now),
+            None
+        );
+        assert_eq!(
+            cursors.take("!a:example.com", "@alice:example.com", now),
+            Some(cursor.clone())
+        );
+        // Taking a cursor consumes it
+        assert_eq!(
+            cursors.take("!a:example.com", "@alice:example.com", now),
+            None
+        );
+
+        cursors.set("!a:example.com", "@alice:example.com", cursor, now);
+        let later = now + Duration::hours(2);
+        assert_eq!(
+            cursors.take("!a:example.com", "@alice:example.com", later),
+            None
+        );
+    }
+}
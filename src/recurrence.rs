@@ -0,0 +1,805 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// Years without any occurrence after which expansion stops. This guards
+/// against rules that can never match (e.g. February 30th), while leaving
+/// room for rare ones such as February 29th on a Monday.
+const MAX_EMPTY_YEARS: i64 = 30;
+
+/// Frequency of a recurrence rule (the FREQ rule part).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A BYDAY entry, e.g. `MO`, `2TU` or `-1FR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    /// Optional ordinal of the weekday within the month or year
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// A parsed RFC 5545 recurrence rule (the value of an RRULE property).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDateTime>,
+    /// Whether UNTIL was given in UTC rather than in DTSTART's local time
+    pub until_utc: bool,
+    pub by_second: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_year_day: Vec<i32>,
+    pub by_week_no: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            until_utc: false,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_week_no: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in value.split(';').filter(|p| !p.is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid RRULE part: {}", part))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(parse_frequency(val)?),
+                "INTERVAL" => {
+                    rule.interval = val
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid INTERVAL: {}", val))?;
+                    if rule.interval == 0 {
+                        return Err(anyhow!("INTERVAL must be positive"));
+                    }
+                }
+                "COUNT" => {
+                    rule.count = Some(val.parse().map_err(|_| anyhow!("Invalid COUNT: {}", val))?)
+                }
+                "UNTIL" => {
+                    rule.until = Some(parse_until(val)?);
+                    rule.until_utc = val.ends_with('Z');
+                }
+                "BYSECOND" => rule.by_second = parse_list(val, 0, 59)?,
+                "BYMINUTE" => rule.by_minute = parse_list(val, 0, 59)?,
+                "BYHOUR" => rule.by_hour = parse_list(val, 0, 23)?,
+                "BYDAY" => {
+                    rule.by_day = val
+                        .split(',')
+                        .map(parse_weekday_num)
+                        .collect::<Result<_>>()?
+                }
+                "BYMONTHDAY" => rule.by_month_day = parse_signed_list(val, 31)?,
+                "BYYEARDAY" => rule.by_year_day = parse_signed_list(val, 366)?,
+                "BYWEEKNO" => rule.by_week_no = parse_signed_list(val, 53)?,
+                "BYMONTH" => rule.by_month = parse_list(val, 1, 12)?,
+                "BYSETPOS" => rule.by_set_pos = parse_signed_list(val, 366)?,
+                "WKST" => rule.week_start = parse_weekday(val)?,
+                // Unknown rule parts (e.g. RSCALE) are ignored
+                _ => {}
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| anyhow!("Missing FREQ in RRULE"))?;
+        Ok(rule)
+    }
+}
+
+impl RecurrenceRule {
+    /// Iterate over the occurrences of this rule for the given DTSTART, in order.
+    ///
+    /// DTSTART itself is always the first occurrence, as required by RFC 5545.
+    /// The iterator is unbounded for rules without COUNT or UNTIL, so callers
+    /// should stop consuming it once past the end of their query window.
+    pub fn occurrences(&self, dtstart: NaiveDateTime) -> Occurrences<'_> {
+        let mut pending = VecDeque::new();
+        pending.push_back(dtstart);
+
+        Occurrences {
+            rule: self,
+            dtstart,
+            period: 0,
+            pending,
+            emitted: 0,
+            finished: false,
+        }
+    }
+
+    /// Start of the n-th period after DTSTART: the first day of the year,
+    /// month or week, the day, or the instant for sub-daily frequencies.
+    /// Returns `None` once the period is out of range.
+    fn period_start(&self, dtstart: NaiveDateTime, period: i64) -> Option<NaiveDateTime> {
+        let step = period.checked_mul(self.interval as i64)?;
+        let start_date = dtstart.date();
+
+        let first_day = match self.frequency {
+            Frequency::Yearly => {
+                let year = i32::try_from(start_date.year() as i64 + step).ok()?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?
+            }
+            Frequency::Monthly => {
+                let month_index = start_date.year() as i64 * 12 + start_date.month0() as i64 + step;
+                let year = i32::try_from(month_index.div_euclid(12)).ok()?;
+                let month = month_index.rem_euclid(12) as u32 + 1;
+                NaiveDate::from_ymd_opt(year, month, 1)?
+            }
+            Frequency::Weekly => {
+                let offset = (7 + start_date.weekday().num_days_from_monday()
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                start_date
+                    .checked_sub_signed(Duration::days(offset as i64))?
+                    .checked_add_signed(Duration::try_weeks(step)?)?
+            }
+            Frequency::Daily => start_date.checked_add_signed(Duration::try_days(step)?)?,
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                let base = self.truncate(dtstart)?;
+                let seconds = step.checked_mul(self.unit()?.num_seconds())?;
+                return base.checked_add_signed(Duration::try_seconds(seconds)?);
+            }
+        };
+        Some(first_day.and_time(NaiveTime::MIN))
+    }
+
+    /// The time unit of a sub-daily frequency.
+    fn unit(&self) -> Option<Duration> {
+        match self.frequency {
+            Frequency::Hourly => Some(Duration::hours(1)),
+            Frequency::Minutely => Some(Duration::minutes(1)),
+            Frequency::Secondly => Some(Duration::seconds(1)),
+            _ => None,
+        }
+    }
+
+    /// Drop the time units finer than a sub-daily frequency.
+    fn truncate(&self, instant: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self.frequency {
+            Frequency::Hourly => instant.with_minute(0)?.with_second(0),
+            Frequency::Minutely => instant.with_second(0),
+            _ => Some(instant),
+        }
+    }
+
+    /// For sub-daily frequencies, the first period after the given empty one
+    /// that can match the BY* filters, skipping the rest of a day, hour or
+    /// minute the filters rule out. Returns `None` if there is nothing to skip.
+    fn next_possible_period(&self, dtstart: NaiveDateTime, period: i64) -> Option<i64> {
+        let length = self.unit()?.num_seconds() * self.interval as i64;
+        let start = self.period_start(dtstart, period)?;
+
+        let resume = if !self.day_matches(start.date(), dtstart.date()) {
+            start.date().succ_opt()?.and_time(NaiveTime::MIN)
+        } else if self.frequency < Frequency::Hourly
+            && !self.by_hour.is_empty()
+            && !self.by_hour.contains(&start.hour())
+        {
+            start.with_minute(0)?.with_second(0)? + Duration::hours(1)
+        } else if self.frequency < Frequency::Minutely
+            && !self.by_minute.is_empty()
+            && !self.by_minute.contains(&start.minute())
+        {
+            start.with_second(0)? + Duration::minutes(1)
+        } else {
+            return None;
+        };
+
+        let elapsed = (resume - self.truncate(dtstart)?).num_seconds();
+        Some((elapsed + length - 1).div_euclid(length))
+    }
+
+    /// All candidate instances for the n-th period after DTSTART, sorted and
+    /// with BYSETPOS applied. Returns `None` once the period is out of range.
+    fn period_candidates(&self, dtstart: NaiveDateTime, period: i64) -> Option<Vec<NaiveDateTime>> {
+        let start = self.period_start(dtstart, period)?;
+        let start_date = dtstart.date();
+
+        let (days, period_time) = match self.frequency {
+            Frequency::Yearly => (days_from(start.date(), days_in_year(start.year())), None),
+            Frequency::Monthly => (
+                days_from(start.date(), days_in_month(start.year(), start.month())),
+                None,
+            ),
+            Frequency::Weekly => (days_from(start.date(), 7), None),
+            Frequency::Daily => (vec![start.date()], None),
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                (vec![start.date()], Some(start.time()))
+            }
+        };
+
+        let times = self.times(dtstart.time(), period_time);
+        let mut candidates: Vec<NaiveDateTime> = days
+            .into_iter()
+            .filter(|day| self.day_matches(*day, start_date))
+            .flat_map(|day| times.iter().map(move |time| day.and_time(*time)))
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+
+        if self.by_set_pos.is_empty() {
+            return Some(candidates);
+        }
+
+        let len = candidates.len() as i32;
+        let mut selected: Vec<NaiveDateTime> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let index = if pos > 0 { pos - 1 } else { len + pos };
+                (0..len)
+                    .contains(&index)
+                    .then(|| candidates[index as usize])
+            })
+            .collect();
+        selected.sort();
+        selected.dedup();
+        Some(selected)
+    }
+
+    /// Times of day for a period. Units at or above the rule frequency come
+    /// from the period itself and are only limited by their BY* list; finer
+    /// units are expanded from their BY* list or taken from DTSTART.
+    fn times(&self, dtstart_time: NaiveTime, period_time: Option<NaiveTime>) -> Vec<NaiveTime> {
+        let unit_values =
+            |by_list: &[u32], unit: Frequency, dtstart_value: u32, period_value: Option<u32>| {
+                match period_value {
+                    Some(value) if self.frequency <= unit => {
+                        if by_list.is_empty() || by_list.contains(&value) {
+                            vec![value]
+                        } else {
+                            Vec::new()
+                        }
+                    }
+                    _ if !by_list.is_empty() => by_list.to_vec(),
+                    _ => vec![dtstart_value],
+                }
+            };
+
+        let hours = unit_values(
+            &self.by_hour,
+            Frequency::Hourly,
+            dtstart_time.hour(),
+            period_time.map(|t| t.hour()),
+        );
+        let minutes = unit_values(
+            &self.by_minute,
+            Frequency::Minutely,
+            dtstart_time.minute(),
+            period_time.map(|t| t.minute()),
+        );
+        let seconds = unit_values(
+            &self.by_second,
+            Frequency::Secondly,
+            dtstart_time.second(),
+            period_time.map(|t| t.second()),
+        );
+
+        let mut times = Vec::new();
+        for hour in &hours {
+            for minute in &minutes {
+                for second in &seconds {
+                    if let Some(time) = NaiveTime::from_hms_opt(*hour, *minute, *second) {
+                        times.push(time);
+                    }
+                }
+            }
+        }
+        times.sort();
+        times
+    }
+
+    /// Whether a day within the current period satisfies the date-level BY*
+    /// rule parts, including the implicit constraints derived from DTSTART.
+    fn day_matches(&self, date: NaiveDate, dtstart: NaiveDate) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+
+        if self.frequency == Frequency::Yearly
+            && !self.by_week_no.is_empty()
+            && !self
+                .by_week_no
+                .iter()
+                .any(|&week| week_no_matches(date, week, self.week_start))
+        {
+            return false;
+        }
+
+        if !self.by_year_day.is_empty()
+            && !self.by_year_day.iter().any(|&day| {
+                let days = days_in_year(date.year()) as i32;
+                let target = if day > 0 { day } else { days + day + 1 };
+                date.ordinal() as i32 == target
+            })
+        {
+            return false;
+        }
+
+        if !self.by_month_day.is_empty()
+            && !self.by_month_day.iter().any(|&day| {
+                let days = days_in_month(date.year(), date.month()) as i32;
+                let target = if day > 0 { day } else { days + day + 1 };
+                date.day() as i32 == target
+            })
+        {
+            return false;
+        }
+
+        if !self.by_day.is_empty() && !self.by_day.iter().any(|wd| self.weekday_matches(date, wd)) {
+            return false;
+        }
+
+        let no_day_rules =
+            self.by_year_day.is_empty() && self.by_month_day.is_empty() && self.by_day.is_empty();
+
+        match self.frequency {
+            Frequency::Yearly if no_day_rules => {
+                if !self.by_week_no.is_empty() {
+                    date.weekday() == dtstart.weekday()
+                } else if self.by_month.is_empty() {
+                    date.month() == dtstart.month() && date.day() == dtstart.day()
+                } else {
+                    date.day() == dtstart.day()
+                }
+            }
+            Frequency::Monthly if no_day_rules => date.day() == dtstart.day(),
+            Frequency::Weekly if self.by_day.is_empty() => date.weekday() == dtstart.weekday(),
+            _ => true,
+        }
+    }
+
+    /// Match a BYDAY entry. Ordinals count within the month for monthly rules
+    /// (and yearly rules with BYMONTH), and within the year for yearly rules.
+    fn weekday_matches(&self, date: NaiveDate, wd: &WeekdayNum) -> bool {
+        if date.weekday() != wd.weekday {
+            return false;
+        }
+
+        let Some(ordinal) = wd.ordinal else {
+            return true;
+        };
+
+        let (index, len) = match self.frequency {
+            Frequency::Monthly => (date.day0(), days_in_month(date.year(), date.month())),
+            Frequency::Yearly if !self.by_month.is_empty() => {
+                (date.day0(), days_in_month(date.year(), date.month()))
+            }
+            Frequency::Yearly => (date.ordinal0(), days_in_year(date.year())),
+            _ => return true,
+        };
+
+        if ordinal > 0 {
+            (index / 7 + 1) as i32 == ordinal
+        } else {
+            ((len - 1 - index) / 7 + 1) as i32 == -ordinal
+        }
+    }
+}
+
+/// Iterator over the occurrences of a [`RecurrenceRule`].
+pub struct Occurrences<'a> {
+    rule: &'a RecurrenceRule,
+    dtstart: NaiveDateTime,
+    period: i64,
+    pending: VecDeque<NaiveDateTime>,
+    emitted: u32,
+    finished: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        // Start of the first period in the current run without occurrences
+        let mut empty_since = None;
+
+        while !self.finished {
+            if let Some(candidate) = self.pending.pop_front() {
+                let count_reached = self.rule.count.is_some_and(|count| self.emitted >= count);
+                let past_until = self.rule.until.is_some_and(|until| candidate > until);
+                if count_reached || past_until {
+                    self.finished = true;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(candidate);
+            }
+
+            let (Some(start), Some(candidates)) = (
+                self.rule.period_start(self.dtstart, self.period),
+                self.rule.period_candidates(self.dtstart, self.period),
+            ) else {
+                self.finished = true;
+                break;
+            };
+            if self.rule.until.is_some_and(|until| start > until) {
+                self.finished = true;
+                break;
+            }
+
+            let period = self.period;
+            self.period += 1;
+            self.pending
+                .extend(candidates.into_iter().filter(|c| *c > self.dtstart));
+            if self.pending.is_empty() {
+                let empty_since = *empty_since.get_or_insert(start);
+                if start - empty_since > Duration::days(366 * MAX_EMPTY_YEARS) {
+                    self.finished = true;
+                } else if let Some(next) = self.rule.next_possible_period(self.dtstart, period) {
+                    self.period = self.period.max(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_frequency(value: &str) -> Result<Frequency> {
+    match value.to_ascii_uppercase().as_str() {
+        "SECONDLY" => Ok(Frequency::Secondly),
+        "MINUTELY" => Ok(Frequency::Minutely),
+        "HOURLY" => Ok(Frequency::Hourly),
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        _ => Err(anyhow!("Invalid FREQ: {}", value)),
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday> {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(anyhow!("Invalid weekday: {}", value)),
+    }
+}
+
+fn parse_weekday_num(value: &str) -> Result<WeekdayNum> {
+    let value = value.trim();
+    let (ordinal, weekday) = value
+        .len()
+        .checked_sub(2)
+        .and_then(|at| value.split_at_checked(at))
+        .ok_or_else(|| anyhow!("Invalid BYDAY value: {}", value))?;
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        let n: i32 = ordinal
+            .trim_start_matches('+')
+            .parse()
+            .map_err(|_| anyhow!("Invalid BYDAY value: {}", value))?;
+        if n == 0 || n.abs() > 53 {
+            return Err(anyhow!("Invalid BYDAY value: {}", value));
+        }
+        Some(n)
+    };
+
+    Ok(WeekdayNum {
+        ordinal,
+        weekday: parse_weekday(weekday)?,
+    })
+}
+
+fn parse_until(value: &str) -> Result<NaiveDateTime> {
+    let trimmed = value.trim_end_matches('Z');
+    if let Ok(dt) = NaiveDateTime::parse_from_str(trimmed, "%Y%m%dT%H%M%S") {
+        return Ok(dt);
+    }
+    // A DATE value includes the whole day
+    NaiveDate::parse_from_str(trimmed, "%Y%m%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .ok_or_else(|| anyhow!("Invalid UNTIL: {}", value))
+}
+
+fn parse_list(value: &str, min: u32, max: u32) -> Result<Vec<u32>> {
+    value
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| anyhow!("Invalid RRULE value: {}", v))
+        })
+        .collect()
+}
+
+fn parse_signed_list(value: &str, max: i32) -> Result<Vec<i32>> {
+    value
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .trim_start_matches('+')
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0 && n.abs() <= max)
+                .ok_or_else(|| anyhow!("Invalid RRULE value: {}", v))
+        })
+        .collect()
+}
+
+fn days_from(first: NaiveDate, count: u32) -> Vec<NaiveDate> {
+    first.iter_days().take(count as usize).collect()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(31)
+}
+
+fn days_in_year(year: i32) -> u32 {
+    if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+        366
+    } else {
+        365
+    }
+}
+
+/// First day of week 1 of a year: the first week starting on `week_start`
+/// that has at least four days in the year.
+fn first_week_start(year: i32, week_start: Weekday) -> Option<NaiveDate> {
+    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let offset =
+        (7 + jan1.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    if offset <= 3 {
+        jan1.checked_sub_signed(Duration::days(offset as i64))
+    } else {
+        jan1.checked_add_signed(Duration::days(7 - offset as i64))
+    }
+}
+
+fn week_no_matches(date: NaiveDate, week: i32, week_start: Weekday) -> bool {
+    let year = date.year();
+    let (Some(this_year), Some(next_year)) = (
+        first_week_start(year, week_start),
+        first_week_start(year + 1, week_start),
+    ) else {
+        return false;
+    };
+
+    // Days before week 1 or on/after next year's week 1 belong to another year
+    if date < this_year || date >= next_year {
+        return false;
+    }
+
+    let week_no = ((date - this_year).num_days() / 7 + 1) as i32;
+    let weeks_in_year = ((next_year - this_year).num_days() / 7) as i32;
+    let target = if week > 0 {
+        week
+    } else {
+        weeks_in_year + week + 1
+    };
+    week_no == target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap()
+    }
+
+    fn expand(rule: &str, dtstart: &str, limit: usize) -> Vec<String> {
+        let rule: RecurrenceRule = rule.parse().unwrap();
+        rule.occurrences(dt(dtstart))
+            .take(limit)
+            .map(|o| o.format("%Y%m%dT%H%M%S").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;INTERVAL=2;COUNT=10;BYDAY=1MO,-1FR;WKST=SU"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.frequency, Frequency::Monthly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.count, Some(10));
+        assert_eq!(
+            rule.by_day,
+            vec![
+                WeekdayNum {
+                    ordinal: Some(1),
+                    weekday: Weekday::Mon
+                },
+                WeekdayNum {
+                    ordinal: Some(-1),
+                    weekday: Weekday::Fri
+                },
+            ]
+        );
+        assert_eq!(rule.week_start, Weekday::Sun);
+
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=FORTNIGHTLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=éM".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=1".parse::<RecurrenceRule>().is_err());
+        // A leap second never occurs, so the rule would never produce a time
+        assert!(
+            "FREQ=MINUTELY;BYSECOND=60"
+                .parse::<RecurrenceRule>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_weekly_with_count() {
+        assert_eq!(
+            expand("FREQ=WEEKLY;COUNT=3", "20251201T100000", 10),
+            vec!["20251201T100000", "20251208T100000", "20251215T100000"]
+        );
+    }
+
+    #[test]
+    fn test_weekly_byday_with_interval_and_until() {
+        // Every other week on Tuesday and Thursday until the end of December 10th
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;UNTIL=20251218T000000Z",
+                "20251202T090000",
+                10
+            ),
+            vec!["20251202T090000", "20251204T090000", "20251216T090000"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_bymonthday_skips_short_months() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYMONTHDAY=31", "20260131T120000", 3),
+            vec!["20260131T120000", "20260331T120000", "20260531T120000"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_last_weekday_with_bysetpos() {
+        // Last working day of the month
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+                "20251031T170000",
+                3
+            ),
+            vec!["20251031T170000", "20251128T170000", "20251231T170000"]
+        );
+    }
+
+    #[test]
+    fn test_yearly_nth_weekday_of_month() {
+        // US Thanksgiving: fourth Thursday of November
+        assert_eq!(
+            expand("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH", "20251127T000000", 3),
+            vec!["20251127T000000", "20261126T000000", "20271125T000000"]
+        );
+    }
+
+    #[test]
+    fn test_daily_with_byhour() {
+        assert_eq!(
+            expand("FREQ=DAILY;BYHOUR=9,17;COUNT=4", "20251201T090000", 10),
+            vec![
+                "20251201T090000",
+                "20251201T170000",
+                "20251202T090000",
+                "20251202T170000"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minutely_with_byhour() {
+        // Each match is followed by the rest of the day without one
+        assert_eq!(
+            expand("FREQ=MINUTELY;INTERVAL=15;BYHOUR=9", "20251201T090000", 6),
+            vec![
+                "20251201T090000",
+                "20251201T091500",
+                "20251201T093000",
+                "20251201T094500",
+                "20251202T090000",
+                "20251202T091500"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hourly_with_bymonth() {
+        // Thousands of hours pass between June 30th and the next June
+        assert_eq!(
+            expand(
+                "FREQ=HOURLY;INTERVAL=6;BYMONTH=6;BYHOUR=12",
+                "20250630T120000",
+                3
+            ),
+            vec!["20250630T120000", "20260601T120000", "20260602T120000"]
+        );
+    }
+
+    #[test]
+    fn test_secondly_with_byminute_and_until() {
+        assert_eq!(
+            expand(
+                "FREQ=SECONDLY;INTERVAL=20;BYHOUR=8;BYMINUTE=30;UNTIL=20251202T083020",
+                "20251201T083000",
+                10
+            ),
+            vec![
+                "20251201T083000",
+                "20251201T083020",
+                "20251201T083040",
+                "20251202T083000",
+                "20251202T083020"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leap_day_rule_skips_empty_years() {
+        // February 29th on a Monday: 2016, then 2044
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;BYDAY=MO",
+                "20160229T100000",
+                2
+            ),
+            vec!["20160229T100000", "20440229T100000"]
+        );
+    }
+
+    #[test]
+    fn test_impossible_rule_terminates() {
+        assert_eq!(
+            expand("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", "20250101T000000", 5),
+            vec!["20250101T000000"]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// The start or end of a calendar event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventTime {
+    /// A specific instant, given in UTC or resolved from a TZID
+    Instant(DateTime<Utc>),
+    /// Floating local time that is not tied to any time zone
+    Floating(NaiveDateTime),
+    /// A whole day (VALUE=DATE)
+    AllDay(NaiveDate),
+}
+
+impl EventTime {
+    /// Wall-clock value used for recurrence arithmetic: UTC for instants,
+    /// the local time for floating times and midnight for all-day dates.
+    pub fn naive(&self) -> NaiveDateTime {
+        match self {
+            EventTime::Instant(dt) => dt.naive_utc(),
+            EventTime::Floating(dt) => *dt,
+            EventTime::AllDay(date) => date.and_time(Default::default()),
+        }
+    }
+
+    /// A time of the same kind with a different wall-clock value.
+    pub fn with_naive(&self, dt: NaiveDateTime) -> EventTime {
+        match self {
+            EventTime::Instant(_) => EventTime::Instant(dt.and_utc()),
+            EventTime::Floating(_) => EventTime::Floating(dt),
+            EventTime::AllDay(_) => EventTime::AllDay(dt.date()),
+        }
+    }
+
+    /// The instant used to compare against the current time. Floating times
+    /// and all-day dates have no zone of their own and are taken as UTC.
+    pub fn to_utc(&self) -> DateTime<Utc> {
+        match self {
+            EventTime::Instant(dt) => *dt,
+            _ => self.naive().and_utc(),
+        }
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        matches!(self, EventTime::AllDay(_))
+    }
+}
+
+impl Add<Duration> for EventTime {
+    type Output = EventTime;
+
+    fn add(self, duration: Duration) -> EventTime {
+        self.with_naive(self.naive() + duration)
+    }
+}
+
+/// Parses the iCal DATE and DATE-TIME forms: `20251203`, `20251203T100000`
+/// and `20251203T100000Z`. This is also the conversion path for callers that
+/// still hold iCal time strings.
+impl FromStr for EventTime {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if let Some(utc) = value.strip_suffix('Z') {
+            return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                .map(|dt| EventTime::Instant(dt.and_utc()))
+                .map_err(|_| anyhow!("Invalid iCal date-time: {}", value));
+        }
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+            return Ok(EventTime::Floating(dt));
+        }
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(EventTime::AllDay)
+            .map_err(|_| anyhow!("Invalid iCal date-time: {}", value))
+    }
+}
+
+/// Formats the time back into its iCal form.
+impl fmt::Display for EventTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventTime::Instant(dt) => write!(f, "{}", dt.format("%Y%m%dT%H%M%SZ")),
+            EventTime::Floating(dt) => write!(f, "{}", dt.format("%Y%m%dT%H%M%S")),
+            EventTime::AllDay(date) => write!(f, "{}", date.format("%Y%m%d")),
+        }
+    }
+}
+
+/// Parse an RFC 5545 DURATION value such as `PT1H30M`, `P1D`, `P2W` or
+/// `-PT15M`.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || anyhow!("Invalid duration: {}", value);
+    let trimmed = value.trim();
+
+    let (sign, rest) = match trimmed.as_bytes().first() {
+        Some(b'-') => (-1, &trimmed[1..]),
+        Some(b'+') => (1, &trimmed[1..]),
+        _ => (1, trimmed),
+    };
+    let rest = rest
+        .strip_prefix('P')
+        .or_else(|| rest.strip_prefix('p'))
+        .ok_or_else(invalid)?;
+
+    let mut total = Duration::zero();
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut has_component = false;
+
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().map_err(|_| invalid())?;
+                number.clear();
+                let component = match (unit, in_time) {
+                    ('W', false) => Duration::try_weeks(n),
+                    ('D', false) => Duration::try_days(n),
+                    ('H', true) => Duration::try_hours(n),
+                    ('M', true) => Duration::try_minutes(n),
+                    ('S', true) => Duration::try_seconds(n),
+                    _ => None,
+                }
+                .ok_or_else(invalid)?;
+                total = total.checked_add(&component).ok_or_else(invalid)?;
+                has_component = true;
+            }
+        }
+    }
+
+    if !number.is_empty() || !has_component {
+        return Err(invalid());
+    }
+
+    Ok(total * sign)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        for value in ["20251203T100000Z", "20251203T100000", "20251203"] {
+            let time: EventTime = value.parse().unwrap();
+            assert_eq!(time.to_string(), value);
+        }
+
+        assert!(matches!(
+            "20251203T100000Z".parse::<EventTime>().unwrap(),
+            EventTime::Instant(_)
+        ));
+        assert!("20251203".parse::<EventTime>().unwrap().is_all_day());
+        assert!("tomorrow".parse::<EventTime>().is_err());
+    }
+
+    #[test]
+    fn test_add_duration_keeps_kind() {
+        let date: EventTime = "20251224".parse().unwrap();
+        assert_eq!((date + Duration::days(2)).to_string(), "20251226");
+
+        let floating: EventTime = "20251203T100000".parse().unwrap();
+        assert_eq!(
+            (floating + Duration::minutes(90)).to_string(),
+            "20251203T113000"
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("P1D").unwrap(), Duration::days(1));
+        assert_eq!(parse_duration("P2W").unwrap(), Duration::weeks(2));
+        assert_eq!(
+            parse_duration("P1DT2H3M4S").unwrap(),
+            Duration::seconds(86400 + 7200 + 180 + 4)
+        );
+        assert_eq!(parse_duration("-PT15M").unwrap(), Duration::minutes(-15));
+        assert!(parse_duration("PT").is_err());
+        assert!(parse_duration("1H").is_err());
+        assert!(parse_duration("PT5").is_err());
+    }
+}
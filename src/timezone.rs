@@ -0,0 +1,325 @@
+use crate::recurrence::{Frequency, RecurrenceRule};
+use anyhow::{Result, anyhow};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, LocalResult, NaiveDateTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use ical::parser::ical::component::IcalTimeZone;
+use std::collections::HashMap;
+
+/// A time zone referenced by a TZID parameter.
+#[derive(Debug, Clone)]
+pub enum CalendarTimeZone {
+    /// A zone from the IANA time zone database, e.g. `Europe/Berlin`
+    Iana(Tz),
+    /// A VTIMEZONE definition embedded in the calendar
+    Custom(VTimeZone),
+}
+
+/// A VTIMEZONE component: a set of STANDARD/DAYLIGHT observances.
+#[derive(Debug, Clone)]
+pub struct VTimeZone {
+    pub tzid: String,
+    pub observances: Vec<Observance>,
+}
+
+/// A STANDARD or DAYLIGHT sub-component of a VTIMEZONE.
+#[derive(Debug, Clone)]
+pub struct Observance {
+    /// First onset, in local time before the transition
+    pub onset: NaiveDateTime,
+    pub offset_from: FixedOffset,
+    pub offset_to: FixedOffset,
+    pub rule: Option<RecurrenceRule>,
+    pub rdates: Vec<NaiveDateTime>,
+}
+
+impl CalendarTimeZone {
+    /// Resolve a TZID, preferring the IANA database over embedded VTIMEZONE
+    /// definitions since feeds often ship abbreviated or stale definitions.
+    pub fn resolve(tzid: &str, definitions: &HashMap<String, VTimeZone>) -> Option<Self> {
+        if let Some(tz) = parse_iana_name(tzid) {
+            return Some(CalendarTimeZone::Iana(tz));
+        }
+        definitions
+            .get(tzid)
+            .map(|def| CalendarTimeZone::Custom(def.clone()))
+    }
+
+    /// Convert a local wall-clock time in this zone to UTC.
+    ///
+    /// Ambiguous times (in a DST fall-back) resolve to the earlier instant and
+    /// non-existent times (in a DST gap) are shifted forward by the gap.
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            CalendarTimeZone::Iana(tz) => match tz.from_local_datetime(&local) {
+                LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+                LocalResult::None => {
+                    let shifted = local + Duration::hours(1);
+                    tz.from_local_datetime(&shifted)
+                        .earliest()
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+                }
+            },
+            CalendarTimeZone::Custom(def) => {
+                let offset = def.offset_at(local, false);
+                Utc.from_utc_datetime(&(local - Duration::seconds(offset.local_minus_utc() as i64)))
+            }
+        }
+    }
+
+    /// Convert a UTC instant to local wall-clock time in this zone.
+    pub fn from_utc(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            CalendarTimeZone::Iana(tz) => utc.with_timezone(tz).naive_local(),
+            CalendarTimeZone::Custom(def) => {
+                let offset = def.offset_at(utc.naive_utc(), true);
+                utc.naive_utc() + Duration::seconds(offset.local_minus_utc() as i64)
+            }
+        }
+    }
+}
+
+impl VTimeZone {
+    /// Build a time zone definition from a parsed VTIMEZONE component.
+    pub fn from_ical(component: &IcalTimeZone) -> Result<Self> {
+        let tzid = component
+            .properties
+            .iter()
+            .find(|p| p.name == "TZID")
+            .and_then(|p| p.value.clone())
+            .ok_or_else(|| anyhow!("Missing TZID in VTIMEZONE"))?;
+
+        let mut observances = Vec::new();
+        for transition in &component.transitions {
+            let value = |name: &str| {
+                transition
+                    .properties
+                    .iter()
+                    .find(|p| p.name == name)
+                    .and_then(|p| p.value.as_deref())
+            };
+
+            let onset = value("DTSTART")
+                .and_then(|v| NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok())
+                .ok_or_else(|| anyhow!("Invalid DTSTART in VTIMEZONE {}", tzid))?;
+            let offset_from = value("TZOFFSETFROM")
+                .and_then(parse_utc_offset)
+                .ok_or_else(|| anyhow!("Invalid TZOFFSETFROM in VTIMEZONE {}", tzid))?;
+            let offset_to = value("TZOFFSETTO")
+                .and_then(parse_utc_offset)
+                .ok_or_else(|| anyhow!("Invalid TZOFFSETTO in VTIMEZONE {}", tzid))?;
+            let rule = value("RRULE").map(str::parse).transpose()?;
+            let rdates = transition
+                .properties
+                .iter()
+                .filter(|p| p.name == "RDATE")
+                .filter_map(|p| p.value.as_deref())
+                .flat_map(|v| v.split(','))
+                .filter_map(|v| NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok())
+                .collect();
+
+            observances.push(Observance {
+                onset,
+                offset_from,
+                offset_to,
+                rule,
+                rdates,
+            });
+        }
+
+        if observances.is_empty() {
+            return Err(anyhow!("VTIMEZONE {} has no observances", tzid));
+        }
+
+        Ok(VTimeZone { tzid, observances })
+    }
+
+    /// The UTC offset in effect at the given time, which is either a local
+    /// wall-clock time or (if `is_utc`) a UTC time.
+    fn offset_at(&self, time: NaiveDateTime, is_utc: bool) -> FixedOffset {
+        let mut latest: Option<(NaiveDateTime, FixedOffset)> = None;
+
+        for observance in &self.observances {
+            // Onsets are expressed in the local time before the transition
+            let bound = if is_utc {
+                time + Duration::seconds(observance.offset_from.local_minus_utc() as i64)
+            } else {
+                time
+            };
+            let Some(onset) = observance.last_onset_before(bound) else {
+                continue;
+            };
+            let onset_utc =
+                onset - Duration::seconds(observance.offset_from.local_minus_utc() as i64);
+            if latest.is_none_or(|(best, _)| onset_utc > best) {
+                latest = Some((onset_utc, observance.offset_to));
+            }
+        }
+
+        match latest {
+            Some((_, offset)) => offset,
+            // Before the first onset, the offset in effect is the one it switches from
+            None => self
+                .observances
+                .iter()
+                .min_by_key(|o| o.onset)
+                .map(|o| o.offset_from)
+                .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap()),
+        }
+    }
+}
+
+impl Observance {
+    fn last_onset_before(&self, bound: NaiveDateTime) -> Option<NaiveDateTime> {
+        let from_rule = match &self.rule {
+            Some(rule) => match self.fast_forward(rule, bound) {
+                // The shifted start is not necessarily an onset itself, so skip it
+                Some(start) => rule
+                    .occurrences(start)
+                    .skip(1)
+                    .take_while(|onset| *onset <= bound)
+                    .last(),
+                None => rule
+                    .occurrences(self.onset)
+                    .take_while(|onset| *onset <= bound)
+                    .last(),
+            },
+            None => Some(self.onset).filter(|onset| *onset <= bound),
+        };
+        let from_rdates = self.rdates.iter().filter(|d| **d <= bound).max().copied();
+        from_rule.max(from_rdates)
+    }
+
+    /// Observances commonly start centuries ago (e.g. `16010101T030000`), so
+    /// for open-ended yearly rules start expanding shortly before the bound.
+    fn fast_forward(&self, rule: &RecurrenceRule, bound: NaiveDateTime) -> Option<NaiveDateTime> {
+        let simple_yearly =
+            rule.frequency == Frequency::Yearly && rule.interval == 1 && rule.count.is_none();
+        let year = bound.year() - 2;
+        if !simple_yearly || year <= self.onset.year() {
+            return None;
+        }
+        self.onset.with_year(year)
+    }
+}
+
+/// Parse a TZID as an IANA zone name. Some producers prefix the name with a
+/// path such as `/mozilla.org/20050126_1/Europe/Berlin`, so trailing path
+/// segments are tried as well.
+pub fn parse_iana_name(tzid: &str) -> Option<Tz> {
+    let tzid = tzid.trim().trim_matches('"');
+    if let Ok(tz) = tzid.parse::<Tz>() {
+        return Some(tz);
+    }
+    let segments: Vec<&str> = tzid.split('/').filter(|s| !s.is_empty()).collect();
+    (1..segments.len()).find_map(|start| segments[start..].join("/").parse::<Tz>().ok())
+}
+
+/// Parse a UTC offset value such as `+0100`, `-0500` or `+053000`.
+fn parse_utc_offset(value: &str) -> Option<FixedOffset> {
+    let (sign, digits) = match value.trim().split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[0..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = if digits.len() == 6 {
+        digits[4..6].parse().ok()?
+    } else {
+        0
+    };
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ical::parser::ical::IcalParser;
+    use indoc::indoc;
+    use std::io::BufReader;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap()
+    }
+
+    #[test]
+    fn test_iana_zone_conversion() {
+        let tz = CalendarTimeZone::resolve("Europe/Berlin", &HashMap::new()).unwrap();
+        // Winter (CET, +01:00) and summer (CEST, +02:00)
+        assert_eq!(
+            tz.to_utc(dt("20251203T100000")).naive_utc(),
+            dt("20251203T090000")
+        );
+        assert_eq!(
+            tz.to_utc(dt("20250703T100000")).naive_utc(),
+            dt("20250703T080000")
+        );
+
+        let prefixed =
+            CalendarTimeZone::resolve("/mozilla.org/20050126_1/America/New_York", &HashMap::new())
+                .unwrap();
+        assert_eq!(
+            prefixed.to_utc(dt("20251203T100000")).naive_utc(),
+            dt("20251203T150000")
+        );
+    }
+
+    #[test]
+    fn test_vtimezone_conversion() {
+        let ical_content = indoc! {"
+            BEGIN:VCALENDAR
+            BEGIN:VTIMEZONE
+            TZID:W. Europe Standard Time
+            BEGIN:STANDARD
+            DTSTART:16010101T030000
+            TZOFFSETFROM:+0200
+            TZOFFSETTO:+0100
+            RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10
+            END:STANDARD
+            BEGIN:DAYLIGHT
+            DTSTART:16010101T020000
+            TZOFFSETFROM:+0100
+            TZOFFSETTO:+0200
+            RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3
+            END:DAYLIGHT
+            END:VTIMEZONE
+            END:VCALENDAR
+        "};
+        let calendar = IcalParser::new(BufReader::new(ical_content.as_bytes()))
+            .next()
+            .unwrap()
+            .unwrap();
+        let def = VTimeZone::from_ical(&calendar.timezones[0]).unwrap();
+        let mut definitions = HashMap::new();
+        definitions.insert(def.tzid.clone(), def);
+
+        let tz = CalendarTimeZone::resolve("W. Europe Standard Time", &definitions).unwrap();
+        assert!(matches!(tz, CalendarTimeZone::Custom(_)));
+        assert_eq!(
+            tz.to_utc(dt("20251203T100000")).naive_utc(),
+            dt("20251203T090000")
+        );
+        assert_eq!(
+            tz.to_utc(dt("20250703T100000")).naive_utc(),
+            dt("20250703T080000")
+        );
+        assert_eq!(
+            tz.from_utc(Utc.from_utc_datetime(&dt("20250703T080000"))),
+            dt("20250703T100000")
+        );
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+0100"), FixedOffset::east_opt(3600));
+        assert_eq!(parse_utc_offset("-0530"), FixedOffset::east_opt(-19800));
+        assert_eq!(parse_utc_offset("+053015"), FixedOffset::east_opt(19815));
+        assert_eq!(parse_utc_offset("0100"), None);
+    }
+}